@@ -1,3 +1,4 @@
+use crate::demo::secured;
 use salvo::prelude::*;
 
 pub mod auth;
@@ -10,7 +11,7 @@ pub fn create_router() -> Router {
     Router::new().push(
         Router::with_path("/demo").push(
             Router::new()
-                .push(Router::with_path("/users").push(user_router))
+                .push(secured(Router::with_path("/users").push(user_router)))
                 .push(Router::with_path("/auth").push(auth_router)),
         ),
     )
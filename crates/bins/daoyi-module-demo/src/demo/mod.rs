@@ -1,23 +1,38 @@
 use daoyi_cloud_common::auth::jwt::middleware::JwtAuthHandler;
+use daoyi_cloud_common::db::constraint;
+use daoyi_cloud_common::error;
+use daoyi_entity_demo::demo::entity::prelude::SysUser;
+use daoyi_entity_demo::demo::error_code;
 use salvo::oapi::RouterExt;
 use salvo::oapi::SecurityRequirement;
 use salvo::prelude::*;
 
 pub mod admin_api;
 
+/// 注册模块的错误码与实体约束映射，需在创建路由、启动服务前调用
+pub fn init() {
+    error::register(error_code::GROUP);
+    constraint::register::<SysUser>();
+}
+
 pub fn create_router() -> Router {
     let admin_router = admin_api::create_router();
     Router::new().push(
         Router::with_path("/admin-api")
             .hoop(JwtAuthHandler::new())
-            .oapi_security(SecurityRequirement::new::<&str, [&str; 0], &str>(
-                "bearer_auth",
-                [],
-            ))
-            .oapi_security(SecurityRequirement::new::<&str, [&str; 0], &str>(
-                "tenant_id",
-                [],
-            ))
             .push(admin_router),
     )
 }
+
+/// 需登录访问的路由，在 OpenAPI 中声明 JWT 与租户 ID 安全要求
+pub fn secured(router: Router) -> Router {
+    router
+        .oapi_security(SecurityRequirement::new::<&str, [&str; 0], &str>(
+            "bearer_auth",
+            [],
+        ))
+        .oapi_security(SecurityRequirement::new::<&str, [&str; 0], &str>(
+            "tenant_id",
+            [],
+        ))
+}
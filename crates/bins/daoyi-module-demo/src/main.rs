@@ -3,6 +3,7 @@ use daoyi_module_demo::demo;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    demo::init();
     let router = demo::create_router();
     app::run("demo", router).await
 }
@@ -1,16 +1,40 @@
 use crate::conf;
+use crate::server::catch_panic;
 use crate::utils::id_utils;
-use crate::{db, logger, server};
+use crate::{db, error, health, i18n, logger, metrics, server, shutdown};
 use salvo::prelude::*;
 
 pub async fn run(app_name: &str, router: Router) -> anyhow::Result<()> {
-    conf::AppConfig::load(app_name)?;
-    logger::init();
+    // 配置加载完成前的日志先由启动日志接收，再写入正式的日志输出
+    let bootstrap = logger::bootstrap();
+    conf::AppConfig::load(app_name).inspect_err(|e| tracing::error!("{:#}", e))?;
+    drop(bootstrap);
+    logger::init(app_name, conf::get().logging(), conf::get().telemetry());
+    if conf::get().server().catch_panic() {
+        catch_panic::install_hook();
+        if catch_panic::PANIC_ABORT {
+            tracing::warn!(
+                "Built with panic = \"abort\", panics in handlers will abort the process"
+            );
+        }
+    }
+    if conf::get().server().expose_error_details() {
+        tracing::warn!("server.expose_error_details is enabled, do not use it in production");
+        error::set_expose_details(true);
+    }
     tracing::info!("Starting app server...");
+    i18n::init(conf::get().i18n())?;
     id_utils::init()?;
+    if conf::get().metrics().enabled() {
+        metrics::init(conf::get().metrics())?;
+    }
 
     db::init().await?;
+    health::init(conf::get().health());
 
-    let srv = server::AppServer::new(conf::get().server());
-    srv.start(router).await
+    let srv = server::AppServer::new(conf::get());
+    let result = srv.start(router).await;
+    // 停机钩子失败时以非零状态码退出
+    let hooks = shutdown::run_hooks(conf::get().server().shutdown().hook_timeout()).await;
+    result.and(hooks)
 }
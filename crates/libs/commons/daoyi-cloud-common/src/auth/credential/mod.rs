@@ -0,0 +1,80 @@
+use crate::conf::CredentialConfig;
+use crate::error::ApiError;
+use crate::i18n;
+use crate::response::write_error_response;
+use salvo::basic_auth::{ask_credentials, parse_credentials};
+use salvo::http::header::AUTHORIZATION;
+use salvo::prelude::*;
+use subtle::ConstantTimeEq;
+
+/// 按 [`CredentialConfig`] 校验固定凭据，用于文档、管理端点等内部路由
+pub struct CredentialGuard {
+    auth: CredentialConfig,
+    realm: &'static str,
+}
+
+impl CredentialGuard {
+    /// `realm` 为 Basic 认证质询中的域名
+    pub fn new(auth: CredentialConfig, realm: &'static str) -> Self {
+        Self { auth, realm }
+    }
+
+    fn authorized(&self, req: &Request) -> bool {
+        match &self.auth {
+            CredentialConfig::Basic { username, password } => {
+                parse_credentials(req, &[AUTHORIZATION])
+                    .is_ok_and(|(u, p)| secure_eq(&u, username) & secure_eq(&p, password))
+            }
+            CredentialConfig::Token { token } => {
+                let bearer = req
+                    .headers()
+                    .get(AUTHORIZATION)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.strip_prefix("Bearer "));
+                let query = req.query::<&str>("token");
+                bearer.or(query).is_some_and(|t| secure_eq(t, token))
+            }
+        }
+    }
+}
+
+/// 恒定时间比较，避免通过响应时间逐字节猜测凭据（长度不同时直接返回）
+fn secure_eq(actual: &str, expected: &str) -> bool {
+    actual.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+#[handler]
+impl CredentialGuard {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        _depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        if self.authorized(req) {
+            return;
+        }
+        if let CredentialConfig::Basic { .. } = self.auth {
+            ask_credentials(res, self.realm);
+        }
+        write_error_response(
+            res,
+            ApiError::Unauthenticated(i18n::t("auth.credential_required", &[])),
+        );
+        ctrl.skip_rest();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secure_eq() {
+        assert!(secure_eq("t0ken", "t0ken"));
+        assert!(!secure_eq("t0kem", "t0ken"));
+        assert!(!secure_eq("t0k", "t0ken"));
+        assert!(!secure_eq("", "t0ken"));
+    }
+}
@@ -1,11 +1,14 @@
 use crate::auth::jwt::{Principal, default_jwt};
 use crate::conf;
 use crate::error::ApiError;
-use crate::response::ApiResponse;
-use salvo::http::StatusCode;
+use crate::i18n;
+use crate::response::write_error_response;
+use salvo::extract::{Extractible, Metadata};
 use salvo::http::header::AUTHORIZATION;
+use salvo::oapi::{Components, EndpointArgRegister, Operation};
 use salvo::prelude::*;
-use salvo::writing::Json;
+use std::fmt::Debug;
+use std::ops::Deref;
 use std::sync::LazyLock;
 
 /// JWT 认证中间件
@@ -28,8 +31,7 @@ impl JwtAuthHandler {
             }
             Ok(false) => {}
             Err(e) => {
-                res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
-                res.render(Json(ApiResponse::<()>::err_msg(e.to_string())));
+                write_error_response(res, e);
                 ctrl.skip_rest();
                 return;
             }
@@ -42,11 +44,11 @@ impl JwtAuthHandler {
                 let token = value
                     .to_str()
                     .map_err(|_| {
-                        ApiError::Unauthenticated(String::from("Authorization请求头无效"))
+                        ApiError::Unauthenticated(i18n::t("auth.header_invalid", &[]))
                     })?
                     .strip_prefix("Bearer ")
                     .ok_or_else(|| {
-                        ApiError::Unauthenticated(String::from("Authorization请求头格式无效"))
+                        ApiError::Unauthenticated(i18n::t("auth.header_malformed", &[]))
                     })?;
                 Ok(token)
             })
@@ -58,23 +60,24 @@ impl JwtAuthHandler {
                     req.extensions_mut().insert(principal);
                 }
                 Err(err) => {
-                    res.status_code(StatusCode::UNAUTHORIZED);
-                    res.render(Json(ApiResponse::<()>::err_msg(err.to_string())));
+                    let error = err
+                        .downcast::<jsonwebtoken::errors::Error>()
+                        .map(ApiError::JWT)
+                        .unwrap_or_else(|err| ApiError::Unauthenticated(err.to_string()));
+                    write_error_response(res, error);
                     ctrl.skip_rest();
-                    return;
                 }
             },
             Ok(None) => {
-                res.status_code(StatusCode::UNAUTHORIZED);
-                res.render(Json(ApiResponse::<()>::err_msg("Authorization请求头缺失")));
+                write_error_response(
+                    res,
+                    ApiError::Unauthenticated(i18n::t("auth.header_missing", &[])),
+                );
                 ctrl.skip_rest();
-                return;
             }
             Err(e) => {
-                res.status_code(StatusCode::UNAUTHORIZED);
-                res.render(Json(ApiResponse::<()>::err_msg(e.to_string())));
+                write_error_response(res, e);
                 ctrl.skip_rest();
-                return;
             }
         }
     }
@@ -92,10 +95,68 @@ impl JwtAuthHandler {
     }
 }
 
+impl Default for JwtAuthHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// 从 Request extensions 中提取 Principal
 pub fn extract_principal(req: &Request) -> Result<Principal, ApiError> {
     req.extensions()
         .get::<Principal>()
         .cloned()
-        .ok_or_else(|| ApiError::Unauthenticated(String::from("未找到认证信息")))
+        .ok_or_else(|| ApiError::Unauthenticated(i18n::t("auth.principal_missing", &[])))
+}
+
+/// 已校验令牌的当前用户，未携带或令牌无效时为 `None`。供先于 [`JwtAuthHandler`] 执行的
+/// Service 级 hoop（限流、语言）使用：已认证时取请求扩展，否则自行校验令牌
+pub fn principal(req: &Request) -> Option<Principal> {
+    if let Some(principal) = req.extensions().get::<Principal>() {
+        return Some(principal.clone());
+    }
+    let token = req
+        .headers()
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+    default_jwt().decode(token).ok()
+}
+
+/// 当前登录用户，需经过 [`JwtAuthHandler`] 认证，未认证时返回 401
+#[derive(Debug, Clone)]
+pub struct CurrentUser(pub Principal);
+
+impl CurrentUser {
+    pub fn into_inner(self) -> Principal {
+        self.0
+    }
+}
+
+impl Deref for CurrentUser {
+    type Target = Principal;
+
+    fn deref(&self) -> &Principal {
+        &self.0
+    }
+}
+
+impl<'ex> Extractible<'ex> for CurrentUser {
+    fn metadata() -> &'static Metadata {
+        static METADATA: Metadata = Metadata::new("CurrentUser");
+        &METADATA
+    }
+
+    async fn extract(
+        req: &'ex mut Request,
+        _depot: &'ex mut Depot,
+    ) -> Result<Self, impl Writer + Send + Debug + 'static> {
+        extract_principal(req).map(Self)
+    }
+}
+
+impl EndpointArgRegister for CurrentUser {
+    /// 认证要求由路由的 `oapi_security` 声明，此处不重复登记
+    fn register(_components: &mut Components, _operation: &mut Operation, _arg: &str) {}
 }
@@ -11,7 +11,7 @@ use std::borrow::Cow;
 use std::sync::LazyLock;
 use std::time::Duration;
 
-static DEFAULT_JWT: LazyLock<JWT> = LazyLock::new(|| JWT::default());
+static DEFAULT_JWT: LazyLock<JWT> = LazyLock::new(JWT::default);
 
 /// JWT 主体信息
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -1 +1,2 @@
+pub mod credential;
 pub mod jwt;
@@ -8,18 +8,28 @@ pub struct AuthConfig {
     ignore_urls: Vec<String>,
 }
 
+/// 文档、管理端点等内部路由的访问保护方式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CredentialConfig {
+    /// HTTP Basic 认证
+    Basic { username: String, password: String },
+    /// 固定令牌，通过 `Authorization: Bearer <token>` 或 `?token=` 传递
+    Token { token: String },
+}
+
 impl AuthConfig {
     pub fn ignored(&self, url: &str) -> ApiResult<bool> {
         path_any_matches(&self.ignore_urls, url)
     }
 }
 
-fn path_matches(pattern: &str, path: &str) -> ApiResult<bool> {
+pub(crate) fn path_matches(pattern: &str, path: &str) -> ApiResult<bool> {
     let glob = Glob::new(pattern)?;
     Ok(glob.is_match(path))
 }
 
-fn path_any_matches<A: AsRef<str>>(patterns: &[A], path: &str) -> ApiResult<bool> {
+pub(crate) fn path_any_matches<A: AsRef<str>>(patterns: &[A], path: &str) -> ApiResult<bool> {
     for pattern in patterns {
         if path_matches(pattern.as_ref(), path)? {
             return Ok(true);
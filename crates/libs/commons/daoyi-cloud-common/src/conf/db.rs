@@ -1,21 +1,69 @@
 use serde::Deserialize;
+use std::fmt::Display;
+
+/// 数据库后端类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    #[default]
+    Mysql,
+    #[serde(alias = "postgresql")]
+    Postgres,
+    Sqlite,
+}
+
+impl DatabaseBackend {
+    /// 对应的 cargo feature 是否已启用
+    pub fn enabled(&self) -> bool {
+        match self {
+            DatabaseBackend::Mysql => cfg!(feature = "mysql"),
+            DatabaseBackend::Postgres => cfg!(feature = "postgres"),
+            DatabaseBackend::Sqlite => cfg!(feature = "sqlite"),
+        }
+    }
+
+    pub fn default_port(&self) -> u16 {
+        match self {
+            DatabaseBackend::Mysql => 3306,
+            DatabaseBackend::Postgres => 5432,
+            DatabaseBackend::Sqlite => 0,
+        }
+    }
+}
+
+impl Display for DatabaseBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatabaseBackend::Mysql => write!(f, "mysql"),
+            DatabaseBackend::Postgres => write!(f, "postgres"),
+            DatabaseBackend::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
 
 #[derive(Debug, Deserialize)]
 pub struct DatabaseConfig {
+    #[serde(default)]
+    backend: DatabaseBackend,
     host: Option<String>,
     port: Option<u16>,
     user: Option<String>,
     password: Option<String>,
+    /// 数据库名；SQLite 下为数据库文件路径，`:memory:` 表示内存数据库
     database: Option<String>,
 }
 
 impl DatabaseConfig {
+    pub fn backend(&self) -> DatabaseBackend {
+        self.backend
+    }
+
     pub fn host(&self) -> &str {
         self.host.as_deref().unwrap_or("localhost")
     }
 
     pub fn port(&self) -> u16 {
-        self.port.unwrap_or(3306)
+        self.port.unwrap_or_else(|| self.backend.default_port())
     }
 
     pub fn user(&self) -> &str {
@@ -29,4 +77,26 @@ impl DatabaseConfig {
     pub fn database(&self) -> &str {
         self.database.as_deref().unwrap_or("daoyi_cloud")
     }
+
+    /// 连接 URL
+    pub fn url(&self) -> String {
+        match self.backend {
+            DatabaseBackend::Mysql | DatabaseBackend::Postgres => format!(
+                "{}://{}:{}@{}:{}/{}",
+                self.backend,
+                self.user(),
+                self.password(),
+                self.host(),
+                self.port(),
+                self.database()
+            ),
+            DatabaseBackend::Sqlite if self.in_memory() => String::from("sqlite::memory:"),
+            DatabaseBackend::Sqlite => format!("sqlite://{}?mode=rwc", self.database()),
+        }
+    }
+
+    /// 是否为 SQLite 内存数据库
+    pub fn in_memory(&self) -> bool {
+        self.backend == DatabaseBackend::Sqlite && self.database() == ":memory:"
+    }
 }
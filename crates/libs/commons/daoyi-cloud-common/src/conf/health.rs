@@ -0,0 +1,55 @@
+use bytesize::ByteSize;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// 健康检查配置
+#[derive(Debug, Default, Deserialize)]
+pub struct HealthConfig {
+    enabled: Option<bool>,
+    /// 是否返回各组件的详细信息
+    show_details: Option<bool>,
+    /// 单个健康指示器的超时时间（秒）
+    timeout: Option<u64>,
+    #[serde(default)]
+    disk_space: DiskSpaceConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DiskSpaceConfig {
+    enabled: Option<bool>,
+    path: Option<String>,
+    /// 剩余空间低于该值时为 DOWN
+    threshold: Option<ByteSize>,
+}
+
+impl HealthConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn show_details(&self) -> bool {
+        self.show_details.unwrap_or(true)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout.unwrap_or(5))
+    }
+
+    pub fn disk_space(&self) -> &DiskSpaceConfig {
+        &self.disk_space
+    }
+}
+
+impl DiskSpaceConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or(".")
+    }
+
+    pub fn threshold(&self) -> u64 {
+        self.threshold.unwrap_or(ByteSize::mib(10)).as_u64()
+    }
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+/// 国际化配置
+#[derive(Debug, Default, Deserialize)]
+pub struct I18nConfig {
+    /// 默认语言，请求未指定或不支持时使用，缺失的翻译也回退到该语言
+    default_locale: Option<String>,
+    /// 支持的语言
+    locales: Option<Vec<String>>,
+    /// 消息文件目录，`{path}/{locale}.yaml` 覆盖内置消息，文件不存在时忽略
+    path: Option<String>,
+}
+
+impl I18nConfig {
+    pub fn default_locale(&self) -> &str {
+        self.default_locale.as_deref().unwrap_or("zh-CN")
+    }
+
+    /// 支持的语言，默认语言排在首位
+    pub fn locales(&self) -> Vec<&str> {
+        let configured = match &self.locales {
+            Some(locales) => locales.iter().map(String::as_str).collect(),
+            None => vec!["zh-CN", "en-US"],
+        };
+        let mut locales = vec![self.default_locale()];
+        for locale in configured {
+            if !locales.contains(&locale) {
+                locales.push(locale);
+            }
+        }
+        locales
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or("resources/i18n")
+    }
+}
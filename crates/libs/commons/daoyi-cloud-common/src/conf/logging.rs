@@ -0,0 +1,139 @@
+use crate::conf::CredentialConfig;
+use bytesize::ByteSize;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// 日志配置，`RUST_LOG` 环境变量优先于 `level` / `targets`
+#[derive(Debug, Default, Deserialize)]
+pub struct LoggingConfig {
+    level: Option<String>,
+    /// 按 target 覆盖日志级别，如 `sqlx: warn`
+    #[serde(default)]
+    targets: BTreeMap<String, String>,
+    #[serde(default)]
+    format: LogFormat,
+    console: Option<bool>,
+    file: Option<FileLogConfig>,
+    /// 是否通过后台线程异步写日志
+    non_blocking: Option<bool>,
+    #[serde(default)]
+    admin: LogAdminConfig,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileLogConfig {
+    #[serde(default)]
+    enabled: bool,
+    path: Option<String>,
+    #[serde(default)]
+    rotation: LogRotation,
+    /// 单个文件大小上限，超过后滚动，可与按时间滚动同时使用
+    max_size: Option<ByteSize>,
+    /// 保留的历史文件数
+    max_files: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    #[default]
+    Daily,
+    Hourly,
+    Never,
+}
+
+/// 运行时调整日志级别的管理端点
+#[derive(Debug, Default, Deserialize)]
+pub struct LogAdminConfig {
+    #[serde(default)]
+    enabled: bool,
+    path: Option<String>,
+    /// 访问保护，未配置时不挂载端点
+    auth: Option<CredentialConfig>,
+    /// 临时调整的最长生效时间（秒），超时后自动恢复
+    max_duration: Option<u64>,
+}
+
+impl LoggingConfig {
+    pub fn level(&self) -> &str {
+        self.level.as_deref().unwrap_or("info")
+    }
+
+    /// 组合为 `EnvFilter` 指令，如 `info,sqlx=warn`
+    pub fn directives(&self) -> String {
+        std::iter::once(self.level().to_string())
+            .chain(
+                self.targets
+                    .iter()
+                    .map(|(target, level)| format!("{target}={level}")),
+            )
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn format(&self) -> LogFormat {
+        self.format
+    }
+
+    pub fn console(&self) -> bool {
+        self.console.unwrap_or(true)
+    }
+
+    /// 已启用的文件日志配置
+    pub fn file(&self) -> Option<&FileLogConfig> {
+        self.file.as_ref().filter(|file| file.enabled)
+    }
+
+    pub fn non_blocking(&self) -> bool {
+        self.non_blocking.unwrap_or(true)
+    }
+
+    pub fn admin(&self) -> &LogAdminConfig {
+        &self.admin
+    }
+}
+
+impl LogAdminConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or("/actuator/loggers")
+    }
+
+    pub fn auth(&self) -> Option<&CredentialConfig> {
+        self.auth.as_ref()
+    }
+
+    pub fn max_duration(&self) -> Duration {
+        Duration::from_secs(self.max_duration.unwrap_or(3600))
+    }
+}
+
+impl FileLogConfig {
+    pub fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or("logs/app.log")
+    }
+
+    pub fn rotation(&self) -> LogRotation {
+        self.rotation
+    }
+
+    pub fn max_size(&self) -> Option<u64> {
+        self.max_size.map(|size| size.as_u64())
+    }
+
+    pub fn max_files(&self) -> usize {
+        self.max_files.unwrap_or(7)
+    }
+}
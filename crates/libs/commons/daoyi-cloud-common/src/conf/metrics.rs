@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+/// Prometheus 指标配置
+#[derive(Debug, Default, Deserialize)]
+pub struct MetricsConfig {
+    enabled: Option<bool>,
+    path: Option<String>,
+    /// 请求耗时直方图的桶（秒）
+    buckets: Option<Vec<f64>>,
+}
+
+impl MetricsConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or("/metrics")
+    }
+
+    pub fn buckets(&self) -> &[f64] {
+        self.buckets.as_deref().unwrap_or(&[
+            0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+        ])
+    }
+}
@@ -1,13 +1,27 @@
 pub mod auth;
 pub mod db;
+pub mod health;
+pub mod i18n;
+pub mod logging;
+pub mod metrics;
+pub mod openapi;
 pub mod server;
+pub mod telemetry;
 
 use anyhow::Context;
-pub use auth::AuthConfig;
+pub use auth::{AuthConfig, CredentialConfig};
 use config::{Config, FileFormat};
-pub use db::DatabaseConfig;
+pub use db::{DatabaseBackend, DatabaseConfig};
+pub use health::HealthConfig;
+pub use i18n::I18nConfig;
+pub use logging::LoggingConfig;
+pub use metrics::MetricsConfig;
+pub use openapi::OpenApiConfig;
 use serde::Deserialize;
-pub use server::ServerConfig;
+pub use server::{
+    CorsConfig, ErrorFormat, ErrorResponseConfig, RateLimitConfig, ServerConfig, ShutdownConfig,
+};
+pub use telemetry::TelemetryConfig;
 use tokio::sync::OnceCell;
 
 static CONFIG: OnceCell<AppConfig> = OnceCell::const_new();
@@ -17,10 +31,23 @@ pub struct AppConfig {
     server: ServerConfig,
     database: DatabaseConfig,
     auth: AuthConfig,
+    #[serde(default)]
+    openapi: OpenApiConfig,
+    #[serde(default)]
+    health: HealthConfig,
+    #[serde(default)]
+    metrics: MetricsConfig,
+    #[serde(default)]
+    telemetry: TelemetryConfig,
+    #[serde(default)]
+    logging: LoggingConfig,
+    #[serde(default)]
+    i18n: I18nConfig,
 }
 
 impl AppConfig {
     pub fn load(app_name: &str) -> anyhow::Result<()> {
+        tracing::info!("Loading conf resources/application-{}.yaml", app_name);
         let config = Config::builder()
             .add_source(
                 config::File::with_name(format!("resources/application-{app_name}").as_str())
@@ -31,7 +58,8 @@ impl AppConfig {
                 config::Environment::with_prefix("APP")
                     .try_parsing(true)
                     .separator("_")
-                    .list_separator(","),
+                    .list_separator(",")
+                    .with_list_parse_key("server.bind"),
             )
             .build()
             .with_context(|| anyhow::anyhow!("Failed to load conf"))?
@@ -54,6 +82,30 @@ impl AppConfig {
     pub fn auth(&self) -> &AuthConfig {
         &self.auth
     }
+
+    pub fn openapi(&self) -> &OpenApiConfig {
+        &self.openapi
+    }
+
+    pub fn health(&self) -> &HealthConfig {
+        &self.health
+    }
+
+    pub fn metrics(&self) -> &MetricsConfig {
+        &self.metrics
+    }
+
+    pub fn telemetry(&self) -> &TelemetryConfig {
+        &self.telemetry
+    }
+
+    pub fn logging(&self) -> &LoggingConfig {
+        &self.logging
+    }
+
+    pub fn i18n(&self) -> &I18nConfig {
+        &self.i18n
+    }
 }
 
 pub fn get() -> &'static AppConfig {
@@ -0,0 +1,128 @@
+use crate::conf::CredentialConfig;
+use serde::Deserialize;
+
+/// OpenAPI 文档配置，未配置时使用默认值并挂载全部文档界面
+#[derive(Debug, Default, Deserialize)]
+pub struct OpenApiConfig {
+    enabled: Option<bool>,
+    title: Option<String>,
+    /// 文档版本，默认为 crate 版本
+    version: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    servers: Vec<OpenApiServerConfig>,
+    contact: Option<OpenApiContactConfig>,
+    /// OpenAPI JSON 路径
+    json_path: Option<String>,
+    /// 错误码目录 JSON 路径
+    error_codes_path: Option<String>,
+    #[serde(default)]
+    swagger_ui: DocUiConfig,
+    #[serde(default)]
+    scalar: DocUiConfig,
+    /// 文档路由访问保护，未配置时不保护
+    auth: Option<CredentialConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenApiServerConfig {
+    url: String,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenApiContactConfig {
+    name: Option<String>,
+    email: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DocUiConfig {
+    enabled: Option<bool>,
+    path: Option<String>,
+}
+
+impl OpenApiConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or("DaoYi Cloud API")
+    }
+
+    pub fn version(&self) -> &str {
+        self.version
+            .as_deref()
+            .unwrap_or(env!("CARGO_PKG_VERSION"))
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn servers(&self) -> &[OpenApiServerConfig] {
+        &self.servers
+    }
+
+    pub fn contact(&self) -> Option<&OpenApiContactConfig> {
+        self.contact.as_ref()
+    }
+
+    pub fn json_path(&self) -> &str {
+        self.json_path.as_deref().unwrap_or("/api-docs/openapi.json")
+    }
+
+    pub fn error_codes_path(&self) -> &str {
+        self.error_codes_path
+            .as_deref()
+            .unwrap_or("/api-docs/error-codes.json")
+    }
+
+    /// 启用时返回 Swagger UI 挂载路径
+    pub fn swagger_ui(&self) -> Option<&str> {
+        self.swagger_ui.path_if_enabled("/swagger-ui")
+    }
+
+    /// 启用时返回 Scalar 挂载路径
+    pub fn scalar(&self) -> Option<&str> {
+        self.scalar.path_if_enabled("/scalar")
+    }
+
+    pub fn auth(&self) -> Option<&CredentialConfig> {
+        self.auth.as_ref()
+    }
+}
+
+impl OpenApiServerConfig {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+impl OpenApiContactConfig {
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+}
+
+impl DocUiConfig {
+    fn path_if_enabled<'a>(&'a self, default: &'a str) -> Option<&'a str> {
+        self.enabled
+            .unwrap_or(true)
+            .then(|| self.path.as_deref().unwrap_or(default))
+    }
+}
@@ -1,12 +1,352 @@
+use anyhow::Context;
+use bytesize::ByteSize;
 use serde::Deserialize;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
 
 #[derive(Debug, Deserialize)]
 pub struct ServerConfig {
     port: Option<u16>,
+    /// 监听地址，可为 IP 或 IP:端口，未指定端口时使用 `port`
+    #[serde(default)]
+    bind: Vec<String>,
+    http2: Option<bool>,
+    /// 可信反向代理的地址或网段，仅来自这些地址的请求才按 `X-Forwarded-For` / `X-Real-IP` 识别客户端 IP
+    #[serde(default)]
+    trusted_proxies: Vec<String>,
+    /// 捕获处理器中的 panic 并返回 500，release 使用 `panic = "abort"` 时无效
+    catch_panic: Option<bool>,
+    /// 内部错误响应中返回错误详情，仅用于开发环境
+    #[serde(default)]
+    expose_error_details: bool,
+    #[serde(default)]
+    error_response: ErrorResponseConfig,
+    tls: Option<TlsConfig>,
+    /// 请求超时（秒），0 表示不限制
+    request_timeout: Option<u64>,
+    max_body_size: Option<ByteSize>,
+    /// 按路由覆盖的请求限制，按顺序匹配第一个
+    #[serde(default)]
+    routes: Vec<RouteLimitConfig>,
+    #[serde(default)]
+    cors: CorsConfig,
+    #[serde(default)]
+    shutdown: ShutdownConfig,
+    #[serde(default)]
+    rate_limit: RateLimitConfig,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TlsConfig {
+    #[serde(default)]
+    enabled: bool,
+    cert: String,
+    key: String,
+    /// 证书热加载检查间隔（秒），0 表示不热加载
+    reload_interval: Option<u64>,
+}
+
+/// 错误响应格式配置
+#[derive(Debug, Default, Deserialize)]
+pub struct ErrorResponseConfig {
+    #[serde(default)]
+    format: ErrorFormat,
+    /// RFC 7807 `type` 的前缀，后接错误码
+    type_base: Option<String>,
+}
+
+/// 错误响应格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorFormat {
+    /// 统一响应结构 `ApiResponse`
+    #[default]
+    Envelope,
+    /// RFC 7807 `application/problem+json`
+    Problem,
+    /// 请求 `Accept` 包含 `application/problem+json` 时使用 RFC 7807，否则使用统一响应结构
+    Negotiate,
+}
+
+/// 跨域配置，未配置任何来源时拒绝所有跨域请求
+#[derive(Debug, Default, Deserialize)]
+pub struct CorsConfig {
+    enabled: Option<bool>,
+    /// 允许的来源，`*` 表示任意来源
+    #[serde(default)]
+    allow_origins: Vec<String>,
+    /// 允许的来源模式，`*` 匹配除 `/` 外的任意字符，如 `https://*.example.com`
+    #[serde(default)]
+    allow_origin_patterns: Vec<String>,
+    allow_methods: Option<Vec<String>>,
+    allow_headers: Option<Vec<String>>,
+    #[serde(default)]
+    expose_headers: Vec<String>,
+    #[serde(default)]
+    allow_credentials: bool,
+    /// 预检结果缓存时间（秒）
+    max_age: Option<u64>,
+}
+
+/// 优雅停机配置
+#[derive(Debug, Default, Deserialize)]
+pub struct ShutdownConfig {
+    /// 等待在途请求完成的最长时间（秒）
+    grace_period: Option<u64>,
+    /// 单个停机钩子的超时时间（秒）
+    hook_timeout: Option<u64>,
+}
+
+/// 限流配置，按顺序匹配第一条规则，未匹配的请求不限流
+#[derive(Debug, Default, Deserialize)]
+pub struct RateLimitConfig {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    rules: Vec<RateLimitRule>,
+}
+
+/// 限流规则：每个限流对象在 `period` 秒内最多 `limit` 次请求（令牌桶，允许突发至 `limit`）
+#[derive(Debug, Deserialize)]
+pub struct RateLimitRule {
+    /// 路径模式，支持 glob，如 `/admin-api/**`
+    path: String,
+    #[serde(default)]
+    key: RateLimitKey,
+    limit: u32,
+    /// 时间窗口（秒）
+    period: Option<u64>,
+}
+
+/// 限流对象
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitKey {
+    /// 客户端 IP
+    #[default]
+    Ip,
+    /// 登录用户，未登录时按客户端 IP
+    User,
+    /// 租户，未识别租户时按客户端 IP
+    Tenant,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RouteLimitConfig {
+    path: String,
+    request_timeout: Option<u64>,
+    max_body_size: Option<ByteSize>,
+}
+
+/// 某个请求路径最终生效的请求限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteLimit {
+    pub request_timeout: Option<Duration>,
+    pub max_body_size: Option<u64>,
 }
 
 impl ServerConfig {
     pub fn port(&self) -> u16 {
         self.port.unwrap_or(8080)
     }
+
+    pub fn bind_addrs(&self) -> anyhow::Result<Vec<SocketAddr>> {
+        if self.bind.is_empty() {
+            return Ok(vec![SocketAddr::from(([0, 0, 0, 0], self.port()))]);
+        }
+        self.bind
+            .iter()
+            .map(|addr| {
+                addr.parse::<SocketAddr>()
+                    .or_else(|_| {
+                        addr.parse::<IpAddr>()
+                            .map(|ip| SocketAddr::new(ip, self.port()))
+                    })
+                    .with_context(|| format!("Invalid bind address: {addr}"))
+            })
+            .collect()
+    }
+
+    pub fn http2(&self) -> bool {
+        self.http2.unwrap_or(true)
+    }
+
+    pub fn trusted_proxies(&self) -> &[String] {
+        &self.trusted_proxies
+    }
+
+    pub fn catch_panic(&self) -> bool {
+        self.catch_panic.unwrap_or(true)
+    }
+
+    pub fn expose_error_details(&self) -> bool {
+        self.expose_error_details
+    }
+
+    pub fn error_response(&self) -> &ErrorResponseConfig {
+        &self.error_response
+    }
+
+    /// 已启用的 TLS 配置
+    pub fn tls(&self) -> Option<&TlsConfig> {
+        self.tls.as_ref().filter(|tls| tls.enabled)
+    }
+
+    pub fn cors(&self) -> &CorsConfig {
+        &self.cors
+    }
+
+    pub fn shutdown(&self) -> &ShutdownConfig {
+        &self.shutdown
+    }
+
+    pub fn rate_limit(&self) -> &RateLimitConfig {
+        &self.rate_limit
+    }
+
+    pub fn request_timeout(&self) -> Option<Duration> {
+        to_timeout(self.request_timeout.unwrap_or(30))
+    }
+
+    pub fn max_body_size(&self) -> u64 {
+        self.max_body_size.unwrap_or(ByteSize::mib(2)).as_u64()
+    }
+
+    /// 未匹配任何 `routes` 时的请求限制
+    pub fn default_limit(&self) -> RouteLimit {
+        RouteLimit {
+            request_timeout: self.request_timeout(),
+            max_body_size: Some(self.max_body_size()),
+        }
+    }
+
+    pub fn routes(&self) -> &[RouteLimitConfig] {
+        &self.routes
+    }
+}
+
+impl RouteLimitConfig {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// 以本路由的配置覆盖全局请求限制
+    pub fn limit(&self, default: RouteLimit) -> RouteLimit {
+        RouteLimit {
+            request_timeout: self
+                .request_timeout
+                .map_or(default.request_timeout, to_timeout),
+            max_body_size: self
+                .max_body_size
+                .map_or(default.max_body_size, |size| Some(size.as_u64())),
+        }
+    }
+}
+
+impl TlsConfig {
+    pub fn cert(&self) -> &str {
+        &self.cert
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn reload_interval(&self) -> Option<Duration> {
+        self.reload_interval
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+}
+
+impl ErrorResponseConfig {
+    pub fn format(&self) -> ErrorFormat {
+        self.format
+    }
+
+    pub fn type_base(&self) -> &str {
+        self.type_base.as_deref().unwrap_or("urn:daoyi-cloud:error:")
+    }
+}
+
+impl CorsConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn allow_origins(&self) -> &[String] {
+        &self.allow_origins
+    }
+
+    pub fn allow_origin_patterns(&self) -> &[String] {
+        &self.allow_origin_patterns
+    }
+
+    pub fn allow_methods(&self) -> Vec<&str> {
+        match &self.allow_methods {
+            Some(methods) => methods.iter().map(String::as_str).collect(),
+            None => vec!["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"],
+        }
+    }
+
+    pub fn allow_headers(&self) -> Vec<&str> {
+        match &self.allow_headers {
+            Some(headers) => headers.iter().map(String::as_str).collect(),
+            None => vec!["authorization", "content-type", "tenant-id"],
+        }
+    }
+
+    pub fn expose_headers(&self) -> &[String] {
+        &self.expose_headers
+    }
+
+    pub fn allow_credentials(&self) -> bool {
+        self.allow_credentials
+    }
+
+    pub fn max_age(&self) -> Duration {
+        Duration::from_secs(self.max_age.unwrap_or(43200))
+    }
+}
+
+impl ShutdownConfig {
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period.unwrap_or(30))
+    }
+
+    pub fn hook_timeout(&self) -> Duration {
+        Duration::from_secs(self.hook_timeout.unwrap_or(10))
+    }
+}
+
+impl RateLimitConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn rules(&self) -> &[RateLimitRule] {
+        &self.rules
+    }
+}
+
+impl RateLimitRule {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn key(&self) -> RateLimitKey {
+        self.key
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    pub fn period(&self) -> Duration {
+        Duration::from_secs(self.period.unwrap_or(60).max(1))
+    }
+}
+
+fn to_timeout(secs: u64) -> Option<Duration> {
+    (secs > 0).then(|| Duration::from_secs(secs))
 }
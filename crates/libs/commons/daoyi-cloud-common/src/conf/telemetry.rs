@@ -0,0 +1,41 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+/// OpenTelemetry 链路追踪配置，需启用 `otel` cargo feature
+#[derive(Debug, Default, Deserialize)]
+pub struct TelemetryConfig {
+    #[serde(default)]
+    enabled: bool,
+    /// 服务名，默认为应用名
+    service_name: Option<String>,
+    /// OTLP/HTTP 采集端点
+    endpoint: Option<String>,
+    /// 导出超时（秒）
+    timeout: Option<u64>,
+    /// 采样比例，0.0 ~ 1.0，上游已采样的请求始终采样
+    sample_ratio: Option<f64>,
+}
+
+impl TelemetryConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn service_name(&self) -> Option<&str> {
+        self.service_name.as_deref()
+    }
+
+    pub fn endpoint(&self) -> &str {
+        self.endpoint
+            .as_deref()
+            .unwrap_or("http://localhost:4318/v1/traces")
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout.unwrap_or(10))
+    }
+
+    pub fn sample_ratio(&self) -> f64 {
+        self.sample_ratio.unwrap_or(1.0).clamp(0.0, 1.0)
+    }
+}
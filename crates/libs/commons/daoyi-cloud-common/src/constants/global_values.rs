@@ -1,11 +1,31 @@
 use regex::Regex;
-use std::cell::LazyCell;
+use std::sync::LazyLock;
 
 pub const PAGE_SIZE_NONE: u64 = 0;
 pub const ROOT_ID: i64 = 0;
-pub const MOBILE_PHONE_REGEX: LazyCell<Regex> = LazyCell::new(|| {
+pub static MOBILE_PHONE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^\+?[1-9]\d{6,14}$").expect("Failed to compile mobile phone regex")
 });
+/// 中国大陆邮政编码
+pub static POSTAL_CODE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[0-8]\d{5}$").expect("Failed to compile postal code regex"));
+/// 中国大陆机动车号牌，含新能源号牌
+pub static PLATE_NUMBER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^[京津沪渝冀豫云辽黑湘皖鲁新苏浙赣鄂桂甘晋蒙陕吉闽贵粤青藏川宁琼使领][A-HJ-NP-Z](?:[A-HJ-NP-Z0-9]{4}[A-HJ-NP-Z0-9挂学警港澳]|[DF][A-HJ-NP-Z0-9]\d{4}|\d{5}[DF])$",
+    )
+    .expect("Failed to compile plate number regex")
+});
+/// 18 位居民身份证号码的格式，校验码由 `validate_id_card` 检查
+pub static ID_CARD_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[1-9]\d{5}(?:18|19|20)\d{2}(?:0[1-9]|1[0-2])(?:0[1-9]|[12]\d|3[01])\d{3}[\dXx]$")
+        .expect("Failed to compile ID card regex")
+});
+/// 统一社会信用代码的格式，校验码由 `validate_credit_code` 检查
+pub static CREDIT_CODE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[0-9A-HJ-NPQRTUWXY]{2}\d{6}[0-9A-HJ-NPQRTUWXY]{10}$")
+        .expect("Failed to compile credit code regex")
+});
 
 #[cfg(test)]
 mod tests {
@@ -77,4 +97,43 @@ mod tests {
     fn test_reject_letters() {
         assert!(!MOBILE_PHONE_REGEX.is_match("+1abc567890"));
     }
+
+    #[test]
+    fn test_postal_code() {
+        assert!(POSTAL_CODE_REGEX.is_match("100080"));
+        assert!(POSTAL_CODE_REGEX.is_match("010000"));
+        assert!(!POSTAL_CODE_REGEX.is_match("900000"));
+        assert!(!POSTAL_CODE_REGEX.is_match("10008"));
+    }
+
+    #[test]
+    fn test_plate_number() {
+        assert!(PLATE_NUMBER_REGEX.is_match("京A12345"));
+        assert!(PLATE_NUMBER_REGEX.is_match("粤B1234学"));
+        // 新能源小型车、大型车
+        assert!(PLATE_NUMBER_REGEX.is_match("沪AD12345"));
+        assert!(PLATE_NUMBER_REGEX.is_match("苏E12345F"));
+    }
+
+    #[test]
+    fn test_reject_plate_number() {
+        assert!(!PLATE_NUMBER_REGEX.is_match("京I12345"));
+        assert!(!PLATE_NUMBER_REGEX.is_match("A12345"));
+        assert!(!PLATE_NUMBER_REGEX.is_match("京A1234"));
+    }
+
+    #[test]
+    fn test_id_card_format() {
+        assert!(ID_CARD_REGEX.is_match("11010519491231002X"));
+        assert!(ID_CARD_REGEX.is_match("11010519491231002x"));
+        assert!(!ID_CARD_REGEX.is_match("110105194913310021"));
+        assert!(!ID_CARD_REGEX.is_match("01010519491231002X"));
+    }
+
+    #[test]
+    fn test_credit_code_format() {
+        assert!(CREDIT_CODE_REGEX.is_match("91350100M000100Y43"));
+        assert!(!CREDIT_CODE_REGEX.is_match("91350100M000100I43"));
+        assert!(!CREDIT_CODE_REGEX.is_match("91350100M000100Y4"));
+    }
 }
@@ -0,0 +1,420 @@
+//! 数据库约束冲突的识别与转换：唯一约束、外键约束冲突转换为 409 [`ApiError::Conflict`]，
+//! 非空、超长转换为 400 [`ApiError::Validation`]，均以字段级错误返回。字段按实体注册的索引 / 约束名映射，
+//! 或由错误中的列名转换为序列化字段名（见 [`EntityConstraints`]），兼容 MySQL、PostgreSQL、SQLite。
+
+use crate::error::{ApiError, format_field_errors, to_field_error};
+use crate::openapi::{FieldName, ValidateSchema};
+use sea_orm::{DbErr, EntityTrait, IdenStatic};
+use std::borrow::Cow;
+use std::sync::RwLock;
+use validator::ValidationError;
+
+static CONSTRAINTS: RwLock<Constraints> = RwLock::new(Vec::new());
+/// 各表的列名与序列化字段名，列名即 `Model` 的 Rust 字段名
+static FIELDS: RwLock<Fields> = RwLock::new(Vec::new());
+
+/// 约束冲突类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationKind {
+    Unique,
+    ForeignKey,
+    NotNull,
+    /// 数据超出列长度，SQLite 不检查长度
+    TooLong,
+}
+
+impl ViolationKind {
+    /// 字段错误的错误码，提示信息默认取 `validation.<code>`
+    pub fn code(self) -> &'static str {
+        match self {
+            ViolationKind::Unique => "unique",
+            ViolationKind::ForeignKey => "foreign_key",
+            ViolationKind::NotNull => "required",
+            ViolationKind::TooLong => "too_long",
+        }
+    }
+}
+
+/// 从数据库错误中识别出的约束冲突，数据库未提供的信息为 `None`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    pub table: Option<String>,
+    /// 索引或约束名
+    pub constraint: Option<String>,
+    pub column: Option<String>,
+    /// 列长度上限，取自 PostgreSQL 超长错误中的类型，如 `character varying(16)`
+    pub max_length: Option<u64>,
+}
+
+/// 索引或约束与参数字段的对应关系
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraint {
+    name: &'static str,
+    column: &'static str,
+    field: &'static str,
+    message: Option<&'static str>,
+}
+
+impl Constraint {
+    /// `name` 为索引或约束名，`column` 为约束所在的列（SQLite 不返回约束名，按列匹配），
+    /// `field` 为错误中的字段路径，使用参数的 JSON 字段名
+    pub fn new(name: &'static str, column: impl IdenStatic, field: &'static str) -> Self {
+        Self {
+            name,
+            column: column.as_str(),
+            field,
+            message: None,
+        }
+    }
+
+    /// 提示信息，同 `#[validate(message)]`，`{消息键}` 按请求语言翻译
+    pub const fn message(mut self, message: &'static str) -> Self {
+        self.message = Some(message);
+        self
+    }
+}
+
+/// 实体的索引与约束映射，通过 [`register`] 注册后生效。`Model` 需派生
+/// [`ValidateSchema`]，未映射约束的列按其序列化字段名返回
+pub trait EntityConstraints: EntityTrait<Model: ValidateSchema> {
+    fn constraints() -> Vec<Constraint>;
+}
+
+/// 注册实体的约束与字段映射，需在服务启动前调用；重复注册时覆盖该实体的映射
+pub fn register<E: EntityConstraints>() {
+    let table = E::default().table_name();
+    let (entity_constraints, entity_fields) = mappings::<E>();
+    let mut constraints = CONSTRAINTS.write().unwrap_or_else(|e| e.into_inner());
+    constraints.retain(|(registered, _)| *registered != table);
+    constraints.extend(entity_constraints);
+    let mut fields = FIELDS.write().unwrap_or_else(|e| e.into_inner());
+    fields.retain(|(registered, _)| *registered != table);
+    fields.extend(entity_fields);
+}
+
+/// 以表名为键的约束映射
+type Constraints = Vec<(&'static str, Constraint)>;
+/// 以表名为键的列序列化字段名
+type Fields = Vec<(&'static str, FieldName)>;
+
+/// 实体的约束映射与列的序列化字段名
+fn mappings<E: EntityConstraints>() -> (Constraints, Fields) {
+    let table = E::default().table_name();
+    (
+        E::constraints().into_iter().map(|c| (table, c)).collect(),
+        E::Model::field_names()
+            .into_iter()
+            .map(|f| (table, f))
+            .collect(),
+    )
+}
+
+/// 识别数据库错误中的约束冲突
+#[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+pub fn inspect(err: &DbErr) -> Option<Violation> {
+    use sea_orm::RuntimeErr;
+    use sea_orm::sqlx::error::{Error, ErrorKind};
+
+    let (DbErr::Exec(RuntimeErr::SqlxError(e)) | DbErr::Query(RuntimeErr::SqlxError(e))) = err
+    else {
+        return None;
+    };
+    let Error::Database(e) = e.as_ref() else {
+        return None;
+    };
+    let kind = match e.kind() {
+        ErrorKind::UniqueViolation => ViolationKind::Unique,
+        ErrorKind::ForeignKeyViolation => ViolationKind::ForeignKey,
+        ErrorKind::NotNullViolation => ViolationKind::NotNull,
+        // SQLSTATE string data right truncation（MySQL 1406 / PostgreSQL）
+        _ if e.code().as_deref() == Some("22001") => ViolationKind::TooLong,
+        _ => return None,
+    };
+    Some(parse(kind, e.message(), e.constraint()))
+}
+
+/// 未启用数据库后端时无法识别
+#[cfg(not(any(feature = "mysql", feature = "postgres", feature = "sqlite")))]
+pub fn inspect(_err: &DbErr) -> Option<Violation> {
+    None
+}
+
+/// 将约束冲突转换为带字段的错误，无法识别时返回 `None`
+pub fn to_api_error(err: &DbErr) -> Option<ApiError> {
+    let violation = inspect(err)?;
+    tracing::warn!(error = %err, "Database constraint violated");
+    let constraints = CONSTRAINTS.read().unwrap_or_else(|e| e.into_inner());
+    let fields = FIELDS.read().unwrap_or_else(|e| e.into_inner());
+    Some(convert(&violation, &constraints, &fields))
+}
+
+/// 只按实体 `E` 的映射转换约束冲突，不读取也不修改 [`register`] 的全局映射，供测试等场景使用
+pub fn to_entity_error<E: EntityConstraints>(err: &DbErr) -> Option<ApiError> {
+    let violation = inspect(err)?;
+    let (constraints, fields) = mappings::<E>();
+    Some(convert(&violation, &constraints, &fields))
+}
+
+/// 字段优先取约束映射，其次将列名转换为序列化字段名；均无法确定时（PostgreSQL 超长错误不含列名）
+/// 返回请求级错误
+fn convert(
+    violation: &Violation,
+    constraints: &[(&'static str, Constraint)],
+    fields: &[(&'static str, FieldName)],
+) -> ApiError {
+    let constraint = find(constraints, violation);
+    let field = constraint
+        .map(|c| c.field)
+        .or_else(|| field_name(fields, violation))
+        .or(violation.column.as_deref())
+        .unwrap_or_default();
+    let mut error = ValidationError::new(violation.kind.code());
+    error.message = constraint.and_then(|c| c.message).map(Cow::from);
+    if let Some(max) = violation.max_length {
+        error.add_param(Cow::from("max"), &max);
+    }
+    if field.is_empty() && violation.max_length.is_some() {
+        error.message = Some(Cow::from("{validation.value_too_long}"));
+    }
+    let errors = vec![to_field_error(field, &error)];
+    let message = format_field_errors(&errors);
+    match violation.kind {
+        ViolationKind::Unique | ViolationKind::ForeignKey => ApiError::Conflict { message, errors },
+        ViolationKind::NotNull | ViolationKind::TooLong => ApiError::Validation { message, errors },
+    }
+}
+
+/// 列对应的序列化字段名；MySQL 非空错误不含表名，按列名在各表中查找
+fn field_name(fields: &[(&'static str, FieldName)], violation: &Violation) -> Option<&'static str> {
+    let column = violation.column.as_deref()?;
+    fields
+        .iter()
+        .filter(|(table, _)| violation.table.as_deref().is_none_or(|t| t == *table))
+        .find(|(_, f)| f.field == column && !f.name.is_empty())
+        .map(|(_, f)| f.name)
+}
+
+/// 按约束名查找映射；SQLite 不返回约束名，唯一约束按表名与列名查找
+fn find(constraints: &[(&'static str, Constraint)], violation: &Violation) -> Option<Constraint> {
+    constraints
+        .iter()
+        .find(|(table, c)| match &violation.constraint {
+            Some(name) => c.name == name,
+            None => {
+                violation.kind == ViolationKind::Unique
+                    && violation.table.as_deref() == Some(*table)
+                    && violation.column.as_deref() == Some(c.column)
+            }
+        })
+        .map(|(_, c)| *c)
+}
+
+/// 从错误信息中提取表名、约束名及列名
+#[cfg_attr(
+    not(any(feature = "mysql", feature = "postgres", feature = "sqlite")),
+    allow(dead_code)
+)]
+fn parse(kind: ViolationKind, message: &str, constraint: Option<&str>) -> Violation {
+    // SQLite：UNIQUE constraint failed: sys_user.account
+    if let Some((_, columns)) = message.split_once("constraint failed: ") {
+        let (table, column) = columns
+            .split(", ")
+            .next()
+            .and_then(|column| column.split_once('.'))
+            .unzip();
+        return Violation {
+            kind,
+            table: table.map(String::from),
+            constraint: None,
+            column: column.map(String::from),
+            max_length: None,
+        };
+    }
+    // MySQL：Duplicate entry 'x' for key 'sys_user.uk_sys_user_account'，5.7 不含表名
+    let (key_table, key) = between(message, "for key '", "'")
+        .map(|key| match key.rsplit_once('.') {
+            Some((table, key)) => (Some(table), Some(key)),
+            None => (None, Some(key)),
+        })
+        .unwrap_or_default();
+    let constraint = constraint
+        .or(key)
+        // MySQL：a foreign key constraint fails (`demo`.`sys_user`, CONSTRAINT `fk` FOREIGN KEY (`dept_id`) ...
+        .or_else(|| between(message, "CONSTRAINT `", "`"))
+        // PostgreSQL：violates unique constraint "uk_sys_user_account"
+        .or_else(|| between(message, "constraint \"", "\""));
+    let column = between(message, "Column '", "'")
+        .or_else(|| between(message, "column '", "'"))
+        .or_else(|| between(message, "Field '", "'"))
+        .or_else(|| between(message, "FOREIGN KEY (`", "`"))
+        // PostgreSQL：null value in column "name" of relation "sys_user"
+        .or_else(|| between(message, "column \"", "\""));
+    let table = key_table
+        .or_else(|| between(message, "relation \"", "\""))
+        .or_else(|| between(message, "on table \"", "\""))
+        .or_else(|| between(message, "`.`", "`"));
+    // PostgreSQL：value too long for type character varying(16)
+    let max_length = message
+        .split_once("value too long for type ")
+        .and_then(|(_, ty)| between(ty, "(", ")"))
+        .and_then(|max| max.parse().ok());
+    Violation {
+        kind,
+        table: table.map(String::from),
+        constraint: constraint.map(String::from),
+        column: column.map(String::from),
+        max_length,
+    }
+}
+
+#[cfg_attr(
+    not(any(feature = "mysql", feature = "postgres", feature = "sqlite")),
+    allow(dead_code)
+)]
+fn between<'a>(message: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let (_, rest) = message.split_once(start)?;
+    rest.split_once(end).map(|(value, _)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation(table: &str, constraint: Option<&str>, column: Option<&str>) -> Violation {
+        Violation {
+            kind: ViolationKind::Unique,
+            table: Some(table.to_string()),
+            constraint: constraint.map(String::from),
+            column: column.map(String::from),
+            max_length: None,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let unique = ViolationKind::Unique;
+        assert_eq!(
+            parse(
+                unique,
+                "Duplicate entry 'wangwu' for key 'sys_user.uk_sys_user_account'",
+                None
+            ),
+            violation("sys_user", Some("uk_sys_user_account"), None)
+        );
+        assert_eq!(
+            parse(
+                unique,
+                "duplicate key value violates unique constraint \"uk_sys_user_account\"",
+                Some("uk_sys_user_account")
+            )
+            .constraint
+            .as_deref(),
+            Some("uk_sys_user_account")
+        );
+        assert_eq!(
+            parse(unique, "UNIQUE constraint failed: sys_user.account", None),
+            violation("sys_user", None, Some("account"))
+        );
+
+        let foreign_key = parse(
+            ViolationKind::ForeignKey,
+            "Cannot add or update a child row: a foreign key constraint fails \
+             (`demo`.`sys_user`, CONSTRAINT `fk_user_dept` FOREIGN KEY (`dept_id`) \
+             REFERENCES `sys_dept` (`id`))",
+            None,
+        );
+        assert_eq!(foreign_key.table.as_deref(), Some("sys_user"));
+        assert_eq!(foreign_key.constraint.as_deref(), Some("fk_user_dept"));
+        assert_eq!(foreign_key.column.as_deref(), Some("dept_id"));
+
+        let not_null = ViolationKind::NotNull;
+        assert_eq!(
+            parse(not_null, "Column 'mobile_phone' cannot be null", None)
+                .column
+                .as_deref(),
+            Some("mobile_phone")
+        );
+        let pg = parse(
+            not_null,
+            "null value in column \"name\" of relation \"sys_user\" violates not-null constraint",
+            None,
+        );
+        assert_eq!(pg.column.as_deref(), Some("name"));
+        assert_eq!(pg.table.as_deref(), Some("sys_user"));
+        assert_eq!(
+            parse(
+                ViolationKind::TooLong,
+                "Data too long for column 'name' at row 1",
+                None
+            )
+            .column
+            .as_deref(),
+            Some("name")
+        );
+    }
+
+    #[test]
+    fn test_find_by_column() {
+        let mobile_phone = Constraint {
+            name: "uk_sys_user_mobile_phone",
+            column: "mobile_phone",
+            field: "mobilePhone",
+            message: Some("{demo.mobile_phone_taken}"),
+        };
+        let constraints = [("sys_user", mobile_phone)];
+        // SQLite 只返回列名，按列匹配后使用参数字段名
+        let sqlite = violation("sys_user", None, Some("mobile_phone"));
+        assert_eq!(find(&constraints, &sqlite), Some(mobile_phone));
+        assert_eq!(
+            find(
+                &constraints,
+                &violation("sys_user", None, Some("mobilePhone"))
+            ),
+            None
+        );
+        assert_eq!(
+            find(
+                &constraints,
+                &violation("sys_dept", None, Some("mobile_phone"))
+            ),
+            None
+        );
+        let mysql = violation("sys_user", Some("uk_sys_user_mobile_phone"), None);
+        assert_eq!(find(&constraints, &mysql), Some(mobile_phone));
+    }
+
+    #[test]
+    fn test_convert_unmapped() {
+        let mobile_phone = FieldName {
+            field: "mobile_phone",
+            name: "mobilePhone",
+            nested: None,
+        };
+        let fields = [("sys_user", mobile_phone)];
+        // MySQL 非空错误不含表名，按列名转换为序列化字段名
+        let not_null = parse(
+            ViolationKind::NotNull,
+            "Column 'mobile_phone' cannot be null",
+            None,
+        );
+        let ApiError::Validation { errors, .. } = convert(&not_null, &[], &fields) else {
+            panic!("expected validation error");
+        };
+        assert_eq!(errors[0].field, "mobilePhone");
+        assert_eq!(errors[0].code, "required");
+
+        // PostgreSQL 超长错误不含列名，返回带长度上限的请求级错误
+        let too_long = parse(
+            ViolationKind::TooLong,
+            "value too long for type character varying(16)",
+            None,
+        );
+        let ApiError::Validation { message, errors } = convert(&too_long, &[], &fields) else {
+            panic!("expected validation error");
+        };
+        assert_eq!(errors[0].field, "");
+        assert_eq!(errors[0].params["max"], 16);
+        assert_eq!(message, "存在长度超过 16 的字段");
+    }
+}
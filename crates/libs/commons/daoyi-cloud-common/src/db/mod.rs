@@ -1,6 +1,13 @@
+pub mod constraint;
+pub mod validate;
+
 use crate::conf;
+use crate::conf::DatabaseConfig;
+use crate::shutdown;
 use anyhow::Context;
-use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, Statement};
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement,
+};
 use std::time::Duration;
 use tokio::sync::OnceCell;
 
@@ -12,16 +19,37 @@ pub fn get() -> &'static DatabaseConnection {
         .unwrap_or_else(|| panic!("Database connection not initialized"))
 }
 
+/// 获取全局连接，未初始化时返回 `None`
+pub fn try_get() -> Option<&'static DatabaseConnection> {
+    DB_CONN.get()
+}
+
 pub async fn init() -> anyhow::Result<()> {
-    let db_conf = conf::get().database();
-    let url = format!(
-        "mysql://{}:{}@{}:{}/{}",
-        db_conf.user(),
-        db_conf.password(),
-        db_conf.host(),
-        db_conf.port(),
-        db_conf.database()
-    );
+    init_with(conf::get().database()).await
+}
+
+/// 按指定配置建立连接并设置为全局连接
+pub async fn init_with(db_conf: &DatabaseConfig) -> anyhow::Result<()> {
+    let dc = connect(db_conf).await?;
+    DB_CONN
+        .set(dc)
+        .with_context(|| "Failed to set database connection")?;
+    shutdown::register("database", || async {
+        get().close_by_ref().await?;
+        tracing::info!("Database connection closed");
+        Ok(())
+    });
+    Ok(())
+}
+
+pub async fn connect(db_conf: &DatabaseConfig) -> anyhow::Result<DatabaseConnection> {
+    let backend = db_conf.backend();
+    if !backend.enabled() {
+        anyhow::bail!(
+            "Database backend `{backend}` is not enabled, rebuild with `--features {backend}`"
+        );
+    }
+    let url = db_conf.url();
     tracing::info!("Connecting to database: {}", url);
     let mut options = ConnectOptions::new(url);
     options
@@ -32,22 +60,30 @@ pub async fn init() -> anyhow::Result<()> {
         .idle_timeout(Duration::from_secs(60))
         .max_lifetime(Duration::from_secs(300))
         .sqlx_logging(false);
-    let dc = Database::connect(options).await?;
+    if db_conf.in_memory() {
+        // 内存数据库每个连接相互独立，只能使用单连接且不能被回收
+        options
+            .min_connections(1)
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None);
+    }
+    let mut dc = Database::connect(options).await?;
+    dc.set_metric_callback(crate::metrics::record_db_query);
     dc.ping().await?;
     tracing::info!("Database connection established");
     log_database_version(&dc).await?;
-    DB_CONN
-        .set(dc)
-        .with_context(|| "Failed to set database connection")?;
-    Ok(())
+    Ok(dc)
 }
 
 async fn log_database_version(dc: &DatabaseConnection) -> anyhow::Result<()> {
+    let backend = dc.get_database_backend();
+    let sql = match backend {
+        DbBackend::Sqlite => "SELECT sqlite_version()",
+        _ => "SELECT VERSION()",
+    };
     let result = dc
-        .query_one_raw(Statement::from_string(
-            dc.get_database_backend(),
-            String::from("SELECT VERSION()"),
-        ))
+        .query_one_raw(Statement::from_string(backend, String::from(sql)))
         .await?
         .ok_or_else(|| anyhow::anyhow!("Failed to get database version"))?;
     let version: String = result.try_get_by_index(0)?;
@@ -0,0 +1,149 @@
+//! 依赖数据库的异步校验（唯一性、引用记录存在及启用），在 `validator` 的语法校验之后执行，
+//! 错误与语法校验相同，以字段级错误通过 `ApiError::Validation` 返回。
+
+use crate::db;
+use crate::error::{ApiError, to_field_error};
+use sea_orm::sea_query::{DynIden, Expr, SelectStatement};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, ExprTrait, Iterable, PrimaryKeyToColumn,
+    QueryFilter, QuerySelect, QueryTrait, Value,
+};
+use std::borrow::Cow;
+use validator::ValidationError;
+
+/// 声明数据库校验规则的参数类型
+pub trait ValidateDb {
+    /// 数据库校验规则，按声明顺序执行
+    fn db_rules(&self) -> Vec<DbRule>;
+
+    /// 使用全局连接执行全部规则；`current` 为更新时当前记录的 ID，唯一性校验时排除该记录，新增时为 `None`
+    fn validate_db(
+        &self,
+        current: Option<i64>,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send {
+        validate_rules(db::get(), self.db_rules(), current)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleKind {
+    Unique,
+    Exists,
+    Enabled,
+}
+
+/// 单条数据库校验规则，`field` 为错误中的字段路径，使用参数的 JSON 字段名
+#[derive(Debug, Clone)]
+pub struct DbRule {
+    field: &'static str,
+    kind: RuleKind,
+    query: SelectStatement,
+    primary_key: Option<(DynIden, DynIden)>,
+    message: Option<Cow<'static, str>>,
+}
+
+impl DbRule {
+    /// `column` 中没有等于字段值的其他记录，错误码 `unique`
+    pub fn unique<E: EntityTrait>(
+        field: &'static str,
+        column: E::Column,
+        value: impl Into<Value>,
+    ) -> Self {
+        Self::new::<E>(field, RuleKind::Unique, column, column, value)
+    }
+
+    /// 存在 `column` 等于字段值的记录，如外键引用，错误码 `exists`
+    pub fn exists<E: EntityTrait>(
+        field: &'static str,
+        column: E::Column,
+        value: impl Into<Value>,
+    ) -> Self {
+        Self::new::<E>(field, RuleKind::Exists, column, column, value)
+    }
+
+    /// 存在 `column` 等于字段值的记录且其 `enabled_column` 为 `true`；
+    /// 记录不存在时错误码为 `exists`，已禁用时为 `disabled`
+    pub fn enabled<E: EntityTrait>(
+        field: &'static str,
+        column: E::Column,
+        enabled_column: E::Column,
+        value: impl Into<Value>,
+    ) -> Self {
+        Self::new::<E>(field, RuleKind::Enabled, column, enabled_column, value)
+    }
+
+    /// 自定义提示信息，同 `#[validate(message)]`，`{消息键}` 按请求语言翻译
+    pub fn message(mut self, message: impl Into<Cow<'static, str>>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    fn new<E: EntityTrait>(
+        field: &'static str,
+        kind: RuleKind,
+        column: E::Column,
+        select: E::Column,
+        value: impl Into<Value>,
+    ) -> Self {
+        let query = E::find()
+            .select_only()
+            .column(select)
+            .filter(column.eq(value))
+            .limit(1)
+            .into_query();
+        Self {
+            field,
+            kind,
+            query,
+            primary_key: E::PrimaryKey::iter()
+                .next()
+                .map(|key| key.into_column().as_column_ref()),
+            message: None,
+        }
+    }
+
+    /// 执行查询，不满足规则时返回校验错误
+    async fn check(
+        mut self,
+        db: &impl ConnectionTrait,
+        current: Option<i64>,
+    ) -> Result<Option<ValidationError>, DbErr> {
+        if self.kind == RuleKind::Unique
+            && let (Some(id), Some(primary_key)) = (current, self.primary_key)
+        {
+            self.query.and_where(Expr::col(primary_key).ne(id));
+        }
+        let row = db.query_one(&self.query).await?;
+        let code = match (self.kind, row) {
+            (RuleKind::Unique, Some(_)) => "unique",
+            (RuleKind::Exists | RuleKind::Enabled, None) => "exists",
+            (RuleKind::Enabled, Some(row)) if !row.try_get_by_index::<bool>(0)? => "disabled",
+            _ => return Ok(None),
+        };
+        Ok(Some(ValidationError {
+            code: Cow::from(code),
+            message: self.message,
+            params: Default::default(),
+        }))
+    }
+}
+
+/// 在指定连接（如事务）上执行规则，按声明顺序汇总全部字段的错误
+pub async fn validate_rules(
+    db: &impl ConnectionTrait,
+    rules: Vec<DbRule>,
+    current: Option<i64>,
+) -> Result<(), ApiError> {
+    let mut errors = Vec::new();
+    for rule in rules {
+        let field = rule.field;
+        if let Some(error) = rule.check(db, current).await? {
+            errors.push(to_field_error(field, &error));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::from_field_errors(errors))
+    }
+}
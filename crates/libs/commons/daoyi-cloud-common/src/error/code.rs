@@ -0,0 +1,139 @@
+use crate::i18n;
+use serde::Serialize;
+use std::fmt::Display;
+use std::sync::RwLock;
+
+static GROUPS: RwLock<Vec<ErrorCodeGroup>> = RwLock::new(Vec::new());
+
+/// 稳定的错误码，客户端应按 `code` 而非提示信息判断错误类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ErrorCode {
+    pub code: i32,
+    /// 常量名，便于客户端生成枚举
+    pub name: &'static str,
+    /// 提示信息模板，`{}` 依次替换为参数
+    pub message: &'static str,
+}
+
+impl ErrorCode {
+    pub const fn new(code: i32, name: &'static str, message: &'static str) -> Self {
+        Self {
+            code,
+            name,
+            message,
+        }
+    }
+
+    /// 当前语言的提示信息，`args` 依次填充 `{}`；没有 `error.<错误码>` 翻译时使用定义中的信息
+    pub fn format(&self, args: &[&dyn Display]) -> String {
+        let template = i18n::text(&format!("error.{}", self.code)).unwrap_or(self.message);
+        i18n::format(template, args)
+    }
+}
+
+/// 一个模块的错误码号段，由 [`error_codes!`](crate::error_codes) 生成
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ErrorCodeGroup {
+    pub module: &'static str,
+    pub start: i32,
+    pub end: i32,
+    pub codes: &'static [ErrorCode],
+}
+
+/// 定义一个模块的错误码号段：生成子模块，包含各错误码常量及汇总的 `GROUP`，
+/// 错误码超出号段时编译失败。业务模块需通过 [`register`] 注册后才会出现在错误码目录中。
+#[macro_export]
+macro_rules! error_codes {
+    (
+        $(#[$group_meta:meta])*
+        $vis:vis mod $group:ident($module:literal, $start:literal..=$end:literal) {
+            $(
+                $(#[$meta:meta])*
+                $name:ident = $code:literal => $message:literal;
+            )*
+        }
+    ) => {
+        $(#[$group_meta])*
+        $vis mod $group {
+            $(
+                $(#[$meta])*
+                pub const $name: $crate::error::ErrorCode =
+                    $crate::error::ErrorCode::new($code, stringify!($name), $message);
+                const _: () = assert!(
+                    $start <= $code && $code <= $end,
+                    concat!("error code `", stringify!($name), "` is out of range")
+                );
+            )*
+
+            /// 号段及全部错误码
+            pub const GROUP: $crate::error::ErrorCodeGroup = $crate::error::ErrorCodeGroup {
+                module: $module,
+                start: $start,
+                end: $end,
+                codes: &[$($name),*],
+            };
+        }
+    };
+}
+
+crate::error_codes! {
+    /// 通用错误码（1–9999），0 表示成功；业务模块从 10000 起按模块分配号段
+    pub mod common("common", 1..=9_999) {
+        /// 未分配错误码的业务异常
+        BIZ = 1 => "{}";
+        /// 其他请求错误（4xx）
+        BAD_REQUEST = 1_000 => "{}";
+        VALIDATION = 1_001 => "参数校验失败: {}";
+        UNAUTHENTICATED = 1_002 => "未授权：{}";
+        TOKEN_INVALID = 1_003 => "认证失败：{}";
+        FORBIDDEN = 1_004 => "禁止访问：{}";
+        NOT_FOUND = 1_005 => "服务器迷路了~";
+        METHOD_NOT_ALLOWED = 1_006 => "请求方法不被允许";
+        REQUEST_TIMEOUT = 1_007 => "请求超时";
+        PAYLOAD_TOO_LARGE = 1_008 => "请求体过大";
+        HTTP_VERSION_NOT_SUPPORTED = 1_009 => "不支持的 HTTP 版本";
+        TOO_MANY_REQUESTS = 1_010 => "请求过于频繁，请稍后再试";
+        /// 数据冲突，如违反唯一约束或外键约束
+        CONFLICT = 1_011 => "数据冲突: {}";
+        /// 内部错误，详情只记录日志
+        INTERNAL = 5_000 => "服务器内部错误，错误 ID：{}";
+    }
+}
+
+/// 注册业务模块的错误码，需在服务启动前调用；号段与已注册模块重叠时 panic
+pub fn register(group: ErrorCodeGroup) {
+    let mut groups = GROUPS.write().unwrap_or_else(|e| e.into_inner());
+    add(&mut groups, group);
+}
+
+/// 错误码目录：通用错误码及已注册模块的错误码，按号段排序
+pub fn catalog() -> Vec<ErrorCodeGroup> {
+    catalog_of(&GROUPS.read().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// 将模块号段加入 `groups`，同一模块重复加入时忽略
+pub(super) fn add(groups: &mut Vec<ErrorCodeGroup>, group: ErrorCodeGroup) {
+    for registered in std::iter::once(&common::GROUP).chain(groups.iter()) {
+        if registered.module == group.module && registered.start == group.start {
+            return;
+        }
+        assert!(
+            group.end < registered.start || group.start > registered.end,
+            "error code range {}..={} of `{}` overlaps {}..={} of `{}`",
+            group.start,
+            group.end,
+            group.module,
+            registered.start,
+            registered.end,
+            registered.module
+        );
+    }
+    groups.push(group);
+}
+
+pub(super) fn catalog_of(groups: &[ErrorCodeGroup]) -> Vec<ErrorCodeGroup> {
+    let mut catalog = vec![common::GROUP];
+    catalog.extend(groups.iter().copied());
+    catalog.sort_by_key(|group| group.start);
+    catalog
+}
@@ -1,8 +1,32 @@
+mod code;
+mod validation;
+
+pub use code::{ErrorCode, ErrorCodeGroup, catalog, common, register};
+pub use validation::{FieldError, field_errors_of, format_validation_errors};
+pub(crate) use validation::{format_field_errors, to_field_error};
+
+use crate::i18n;
+use crate::openapi::ValidateSchema;
 use crate::response::ApiResponse;
+use crate::utils::id_utils;
 use salvo::http::StatusCode;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub type ApiResult<T> = Result<T, ApiError>;
 
+static EXPOSE_DETAILS: AtomicBool = AtomicBool::new(false);
+
+/// 是否在内部错误响应中返回错误详情，仅用于开发环境
+pub fn set_expose_details(expose: bool) {
+    EXPOSE_DETAILS.store(expose, Ordering::Relaxed);
+}
+
+/// 内部错误返回给客户端的通用信息，可凭错误 ID 在日志中查找详情
+pub fn internal_error_message(error_id: &str) -> String {
+    common::INTERNAL.format(&[&error_id])
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
     #[error("服务器迷路了~")]
@@ -13,67 +37,351 @@ pub enum ApiError {
     Biz(String),
     #[error("错误: {0}")]
     Internal(#[from] anyhow::Error),
+    /// 数据库异常；约束冲突经 `From<DbErr>` 转换为 [`ApiError::Conflict`] 或 [`ApiError::Validation`]
     #[error("数据库异常: {0}")]
-    DbErr(#[from] sea_orm::DbErr),
-    #[error("参数校验失败: {0}")]
-    Validation(String),
+    DbErr(sea_orm::DbErr),
+    /// 参数校验失败，`errors` 为字段级错误，通过响应的 `data` 返回
+    #[error("参数校验失败: {message}")]
+    Validation {
+        message: String,
+        errors: Vec<FieldError>,
+    },
+    /// 数据冲突（唯一约束、外键约束），`errors` 为字段级错误，通过响应的 `data` 返回
+    #[error("数据冲突: {message}")]
+    Conflict {
+        message: String,
+        errors: Vec<FieldError>,
+    },
     #[error("密码错误: {0}")]
     Bcrypt(#[from] bcrypt::BcryptError),
     #[error("认证失败：{0}")]
     JWT(#[from] jsonwebtoken::errors::Error),
     #[error("未授权：{0}")]
     Unauthenticated(String),
+    #[error("禁止访问：{0}")]
+    Forbidden(String),
     #[error("Glob异常: {0}")]
     Glob(#[from] wax::BuildError),
+    #[error("请求超时")]
+    RequestTimeout,
+    #[error("请求体过大")]
+    PayloadTooLarge,
+    #[error("不支持的 HTTP 版本")]
+    HttpVersionNotSupported,
+    #[error("请求过于频繁，请稍后再试")]
+    TooManyRequests,
+    /// 带错误码的业务异常，通过 [`ApiError::new`] 构造，响应时按请求语言渲染提示信息
+    #[error("{}", fill(.code.message, .args))]
+    Code { code: ErrorCode, args: Vec<String> },
 }
 
-/// 将 validator 的 ValidationErrors 格式化为易读的字段级错误信息
-pub fn format_validation_errors(errors: &validator::ValidationErrors) -> String {
-    format_to_vec(errors).join("; ")
+impl From<ErrorCode> for ApiError {
+    fn from(code: ErrorCode) -> Self {
+        ApiError::new(code, &[])
+    }
 }
 
-fn format_to_vec(errors: &validator::ValidationErrors) -> Vec<String> {
-    use validator::ValidationErrorsKind;
-    errors
-        .errors()
-        .iter()
-        .flat_map(|(field, errors_kind)| match errors_kind {
-            ValidationErrorsKind::Field(field_errors) => field_errors
-                .iter()
-                .map(|error| {
-                    let message = error
-                        .message
-                        .as_ref()
-                        .map(|m| m.to_string())
-                        .unwrap_or_else(|| "验证失败".to_string());
-                    format!("[{}]{}", field, message)
-                })
-                .collect::<Vec<_>>(),
-            ValidationErrorsKind::Struct(struct_errors) => format_to_vec(struct_errors),
-            ValidationErrorsKind::List(list_errors) => list_errors
-                .iter()
-                .flat_map(|(_index, errors)| format_to_vec(errors))
-                .collect::<Vec<_>>(),
-        })
-        .collect()
+impl From<sea_orm::DbErr> for ApiError {
+    /// 可识别的约束冲突转换为带字段的错误，见 [`db::constraint`](crate::db::constraint)
+    fn from(err: sea_orm::DbErr) -> Self {
+        crate::db::constraint::to_api_error(&err).unwrap_or(ApiError::DbErr(err))
+    }
+}
+
+fn fill(template: &str, args: &[String]) -> String {
+    let args: Vec<&dyn Display> = args.iter().map(|arg| arg as &dyn Display).collect();
+    i18n::format(template, &args)
 }
 
 impl ApiError {
+    /// 不针对具体字段的参数错误，如请求体无法解析
+    pub fn validation(message: impl Into<String>) -> Self {
+        ApiError::Validation {
+            message: message.into(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// `T` 的参数校验失败，字段路径按 `T` 的序列化名称转换
+    pub fn invalid<T: ValidateSchema>(errors: &validator::ValidationErrors) -> Self {
+        Self::from_field_errors(field_errors_of::<T>(errors))
+    }
+
+    /// 已转换为 JSON 字段名的字段错误
+    pub(crate) fn from_field_errors(errors: Vec<FieldError>) -> Self {
+        ApiError::Validation {
+            message: format_field_errors(&errors),
+            errors,
+        }
+    }
+
+    /// 按错误码构造业务异常，`args` 依次填充提示信息中的 `{}`
+    pub fn new(code: ErrorCode, args: &[&dyn Display]) -> Self {
+        ApiError::Code {
+            code,
+            args: args.iter().map(ToString::to_string).collect(),
+        }
+    }
+
     pub fn status_code(&self) -> StatusCode {
         match self {
             ApiError::NotFound => StatusCode::NOT_FOUND,
             ApiError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
-            ApiError::Biz(_) => StatusCode::OK,
+            ApiError::Biz(_) | ApiError::Code { .. } => StatusCode::OK,
             ApiError::Internal(_)
             | ApiError::DbErr(_)
             | ApiError::Bcrypt(_)
             | ApiError::Glob(_) => StatusCode::INTERNAL_SERVER_ERROR,
-            ApiError::Validation(_) => StatusCode::BAD_REQUEST,
+            ApiError::Validation { .. } => StatusCode::BAD_REQUEST,
+            ApiError::Conflict { .. } => StatusCode::CONFLICT,
             ApiError::JWT(_) | ApiError::Unauthenticated(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::RequestTimeout => StatusCode::REQUEST_TIMEOUT,
+            ApiError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::HttpVersionNotSupported => StatusCode::HTTP_VERSION_NOT_SUPPORTED,
+            ApiError::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 
-    pub fn to_api_response(&self) -> ApiResponse<()> {
-        ApiResponse::<()>::err_msg(self.to_string())
+    /// 响应体中的错误码
+    pub fn code(&self) -> i32 {
+        self.error_code().code
+    }
+
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ApiError::NotFound => common::NOT_FOUND,
+            ApiError::MethodNotAllowed => common::METHOD_NOT_ALLOWED,
+            ApiError::Biz(_) => common::BIZ,
+            ApiError::Internal(_)
+            | ApiError::DbErr(_)
+            | ApiError::Bcrypt(_)
+            | ApiError::Glob(_) => common::INTERNAL,
+            ApiError::Validation { .. } => common::VALIDATION,
+            ApiError::Conflict { .. } => common::CONFLICT,
+            ApiError::JWT(_) => common::TOKEN_INVALID,
+            ApiError::Unauthenticated(_) => common::UNAUTHENTICATED,
+            ApiError::Forbidden(_) => common::FORBIDDEN,
+            ApiError::RequestTimeout => common::REQUEST_TIMEOUT,
+            ApiError::PayloadTooLarge => common::PAYLOAD_TOO_LARGE,
+            ApiError::HttpVersionNotSupported => common::HTTP_VERSION_NOT_SUPPORTED,
+            ApiError::TooManyRequests => common::TOO_MANY_REQUESTS,
+            ApiError::Code { code, .. } => *code,
+        }
+    }
+
+    /// 当前语言的提示信息
+    pub fn message(&self) -> String {
+        let code = self.error_code();
+        match self {
+            ApiError::Biz(message)
+            | ApiError::Validation { message, .. }
+            | ApiError::Conflict { message, .. }
+            | ApiError::Unauthenticated(message)
+            | ApiError::Forbidden(message) => code.format(&[message]),
+            ApiError::JWT(e) => code.format(&[e]),
+            ApiError::Code { args, .. } => {
+                let args: Vec<&dyn Display> = args.iter().map(|arg| arg as &dyn Display).collect();
+                code.format(&args)
+            }
+            _ => code.format(&[]),
+        }
+    }
+
+    /// 是否为内部错误，其详情（SQL、错误链等）不应返回给客户端
+    pub fn is_internal(&self) -> bool {
+        matches!(
+            self,
+            ApiError::Internal(_) | ApiError::DbErr(_) | ApiError::Bcrypt(_) | ApiError::Glob(_)
+        )
+    }
+
+    /// 完整错误信息，包含 anyhow 错误链，仅用于日志
+    pub fn detail(&self) -> String {
+        match self {
+            ApiError::Internal(e) => format!("错误: {e:#}"),
+            _ => self.to_string(),
+        }
+    }
+
+    /// 转为响应体；内部错误以错误 ID 记录完整日志，客户端只得到通用信息与错误 ID
+    /// 参数校验失败及数据冲突时 `data` 为字段级错误
+    pub fn to_api_response(&self) -> ApiResponse<Vec<FieldError>> {
+        let data = match self {
+            ApiError::Validation { errors, .. } | ApiError::Conflict { errors, .. }
+                if !errors.is_empty() =>
+            {
+                Some(errors.clone())
+            }
+            _ => None,
+        };
+        ApiResponse::new(
+            self.code(),
+            self.client_message(EXPOSE_DETAILS.load(Ordering::Relaxed)),
+            data,
+        )
+    }
+
+    fn client_message(&self, expose_details: bool) -> String {
+        if !self.is_internal() {
+            return self.message();
+        }
+        let error_id = id_utils::xid();
+        let detail = self.detail();
+        tracing::error!(target: "api_error", error_id, "{}", detail);
+        if expose_details {
+            let template = i18n::text("error.internal_detail").unwrap_or("{detail} ({error_id})");
+            i18n::format_named(template, |name| match name {
+                "detail" => Some(detail.clone()),
+                "error_id" => Some(error_id.clone()),
+                _ => None,
+            })
+        } else {
+            internal_error_message(&error_id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validator::Validate;
+
+    #[tokio::test]
+    async fn test_hide_internal_details() {
+        let error = ApiError::DbErr(sea_orm::DbErr::Custom(String::from(
+            "SELECT * FROM sys_user: no such table",
+        )));
+        let message = error.client_message(false);
+        assert!(message.starts_with("服务器内部错误，错误 ID："));
+        assert!(!message.contains("sys_user"));
+        assert!(error.client_message(true).contains("no such table"));
+
+        let error = ApiError::Internal(
+            anyhow::anyhow!("connection refused").context("Failed to load user"),
+        );
+        let message = error.client_message(true);
+        assert!(message.contains("Failed to load user: connection refused（错误 ID："));
+        let message = i18n::scope("en-US", async { error.client_message(true) }).await;
+        assert!(message.contains("Failed to load user: connection refused (error ID: "));
+
+        let error = ApiError::validation("[name]不能为空");
+        assert_eq!(error.client_message(false), "参数校验失败: [name]不能为空");
+    }
+
+    #[derive(serde::Deserialize, Validate, salvo::oapi::ToSchema, ValidateSchema)]
+    struct Params {
+        #[validate(length(min = 2, max = 4))]
+        nick_name: String,
+        #[serde(rename = "mobile")]
+        #[validate(custom(function = "crate::extract::validations::validate_mobile_phone"))]
+        phone: String,
+        #[validate(nested)]
+        page: crate::pojo::pagination::PageParam,
+    }
+
+    #[tokio::test]
+    async fn test_localized_validation() {
+        let params = Params {
+            nick_name: String::from("a"),
+            phone: String::from("123"),
+            page: crate::pojo::pagination::PageParam {
+                page_no: 0,
+                page_size: 500,
+            },
+        };
+        let errors = params.validate().unwrap_err();
+        assert_eq!(
+            format_validation_errors::<Params>(&errors),
+            "[mobile]手机号格式不正确; [nick_name]长度必须在 2 到 4 之间; \
+             [page.pageNo]页码最小值为 1; [page.pageSize]每页条数最大值为 200"
+        );
+        let body = i18n::scope("en-US", async {
+            ApiError::invalid::<Params>(&errors).to_api_response()
+        })
+        .await;
+        assert!(body.msg.starts_with("Validation failed: "));
+        assert!(
+            body.msg
+                .contains("[mobile]is not a valid mobile phone number")
+        );
+        let data = body.data.unwrap();
+        let fields: Vec<_> = data.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["mobile", "nick_name", "page.pageNo", "page.pageSize"]
+        );
+        assert_eq!(data[1].code, "length");
+        assert_eq!(data[1].message, "length must be between 2 and 4");
+        // 不返回字段值
+        assert_eq!(
+            serde_json::to_value(&data[1].params).unwrap(),
+            serde_json::json!({ "min": 2, "max": 4 })
+        );
+        assert_eq!(data[3].message, "page size must be at most 200");
+    }
+
+    #[derive(serde::Deserialize, Validate, salvo::oapi::ToSchema, ValidateSchema)]
+    #[serde(rename_all = "camelCase")]
+    struct Item {
+        #[validate(length(min = 1))]
+        item_name: String,
+    }
+
+    #[derive(serde::Deserialize, Validate, salvo::oapi::ToSchema, ValidateSchema)]
+    struct Order {
+        #[validate(nested)]
+        items: Vec<Item>,
+    }
+
+    #[test]
+    fn test_sort_list_indices() {
+        let item = |name: &str| Item {
+            item_name: name.to_string(),
+        };
+        let mut items: Vec<_> = (0..11).map(|_| item("a")).collect();
+        items[2] = item("");
+        items[10] = item("");
+        let errors = Order { items }.validate().unwrap_err();
+        let fields: Vec<_> = field_errors_of::<Order>(&errors)
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(fields, ["items[2].itemName", "items[10].itemName"]);
+    }
+
+    crate::error_codes! {
+        mod test_code("test", 90_000..=90_999) {
+            ORDER_STOCK_SHORTAGE = 90_001 => "商品{}库存不足，剩余 {} 件";
+        }
+    }
+
+    #[test]
+    fn test_error_code() {
+        let error = ApiError::new(test_code::ORDER_STOCK_SHORTAGE, &[&"A001", &3]);
+        let body = error.to_api_response();
+        assert_eq!(body.code, 90_001);
+        assert_eq!(body.msg, "商品A001库存不足，剩余 3 件");
+        assert_eq!(error.status_code(), StatusCode::OK);
+        assert_eq!(
+            test_code::ORDER_STOCK_SHORTAGE.format(&[&"A001"]),
+            "商品A001库存不足，剩余 {} 件"
+        );
+        assert_eq!(ApiError::TooManyRequests.code(), 1_010);
+
+        // 使用局部号段列表，不修改全局注册
+        let mut groups = Vec::new();
+        code::add(&mut groups, test_code::GROUP);
+        code::add(&mut groups, test_code::GROUP);
+        let catalog = code::catalog_of(&groups);
+        assert_eq!(catalog[0].module, "common");
+        assert_eq!(catalog.iter().filter(|g| g.module == "test").count(), 1);
+        let overlapping = ErrorCodeGroup {
+            module: "other",
+            start: 90_500,
+            end: 91_000,
+            codes: &[],
+        };
+        assert!(std::panic::catch_unwind(move || code::add(&mut groups, overlapping)).is_err());
     }
 }
@@ -0,0 +1,171 @@
+use crate::i18n;
+use crate::openapi::{FieldName, ValidateSchema};
+use salvo::oapi::ToSchema;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+/// 字段级校验错误
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct FieldError {
+    /// 字段路径，如 `page.pageSize`、`items[0].name`，按参数类型的序列化名称转换（见 [`field_errors_of`]）；
+    /// 结构体级错误为所在对象的路径
+    pub field: String,
+    /// 校验错误码，如 `length`、`range`、`mobile_phone`
+    pub code: String,
+    /// 当前语言的错误信息
+    pub message: String,
+    /// 校验参数，如 `min`、`max`，不含字段值
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub params: Map<String, Value>,
+}
+
+/// 将 `T` 的 ValidationErrors 展开为字段级错误，按字段路径排序；
+/// 字段路径按 `T` 的 `#[serde(rename_all)]` / `#[serde(rename)]` 使用 JSON 字段名
+pub fn field_errors_of<T: ValidateSchema>(errors: &ValidationErrors) -> Vec<FieldError> {
+    let mut field_errors = Vec::new();
+    collect("", errors, &T::field_names(), &mut field_errors);
+    field_errors.sort_by(|a, b| compare_paths(&a.field, &b.field));
+    field_errors
+}
+
+/// 将 `T` 的 ValidationErrors 格式化为易读的字段级错误信息
+pub fn format_validation_errors<T: ValidateSchema>(errors: &ValidationErrors) -> String {
+    format_field_errors(&field_errors_of::<T>(errors))
+}
+
+pub(crate) fn format_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|error| match error.field.as_str() {
+            "" => error.message.clone(),
+            field => format!("[{}]{}", field, error.message),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// 字段路径中的字段名或列表下标
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Segment<'a> {
+    Name(&'a str),
+    Index(usize),
+}
+
+/// 比较字段路径，列表下标按数值比较（`items[2]` 在 `items[10]` 之前）
+fn compare_paths(a: &str, b: &str) -> Ordering {
+    fn segments(path: &str) -> Vec<Segment<'_>> {
+        let mut parts = path.split('[');
+        let mut segments = vec![Segment::Name(parts.next().unwrap_or_default())];
+        for part in parts {
+            let (index, rest) = part.split_once(']').unwrap_or((part, ""));
+            segments.push(index.parse().map_or(Segment::Name(index), Segment::Index));
+            segments.push(Segment::Name(rest));
+        }
+        segments
+    }
+    segments(a).cmp(&segments(b))
+}
+
+fn collect(
+    path: &str,
+    errors: &ValidationErrors,
+    names: &[FieldName],
+    field_errors: &mut Vec<FieldError>,
+) {
+    for (field, kind) in errors.errors() {
+        let name = names.iter().find(|name| name.field == *field);
+        let path = join(path, name.map_or(field, |name| name.name));
+        let nested = name.and_then(|name| name.nested).map(|nested| nested());
+        let nested = nested.as_deref().unwrap_or_default();
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                field_errors.extend(errors.iter().map(|error| field_error(path.clone(), error)))
+            }
+            ValidationErrorsKind::Struct(errors) => collect(&path, errors, nested, field_errors),
+            ValidationErrorsKind::List(errors) => {
+                for (index, errors) in errors {
+                    collect(&format!("{path}[{index}]"), errors, nested, field_errors);
+                }
+            }
+        }
+    }
+}
+
+/// 单个字段的错误，`path` 为已转换的字段路径
+fn field_error(path: String, error: &ValidationError) -> FieldError {
+    FieldError {
+        field: path,
+        code: error.code.to_string(),
+        message: validation_message(error),
+        params: error
+            .params
+            .iter()
+            .filter(|(name, _)| *name != "value")
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect(),
+    }
+}
+
+/// 非 `validator` 产生的字段错误（如数据库约束冲突），`field` 为 JSON 字段名，提示信息规则同校验错误
+pub(crate) fn to_field_error(field: &str, error: &ValidationError) -> FieldError {
+    field_error(field.to_string(), error)
+}
+
+/// 拼接字段路径，`name` 为空时（`#[serde(flatten)]`）沿用所在对象的路径
+fn join(parent: &str, name: &str) -> String {
+    // 结构体级（schema）校验错误
+    if name == "__all__" || name.is_empty() {
+        return parent.to_string();
+    }
+    let name = name.trim_start_matches("r#");
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{parent}.{name}")
+    }
+}
+
+/// 当前语言的校验错误信息：`message` 为 `{key}` 时按消息键翻译，其他 `message` 原样返回，
+/// 未指定时按错误码查找 `validation.<code>`；模板中的 `{min}` 等替换为校验参数
+fn validation_message(error: &ValidationError) -> String {
+    let param = |name: &str| {
+        error.params.get(name).map(|value| match value {
+            Value::String(value) => value.clone(),
+            Value::Array(values) => values
+                .iter()
+                .map(|value| match value {
+                    Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            value => value.to_string(),
+        })
+    };
+    if let Some(message) = &error.message {
+        let Some(key) = message.strip_prefix('{').and_then(|m| m.strip_suffix('}')) else {
+            return message.to_string();
+        };
+        return match i18n::text(key) {
+            Some(template) => i18n::format_named(template, param),
+            None => key.to_string(),
+        };
+    }
+    // 长度、范围按给定的参数选择对应的提示
+    let has = |name: &str| error.params.contains_key(name);
+    let key = match error.code.as_ref() {
+        code @ ("length" | "range") => match (has("equal"), has("min"), has("max")) {
+            (true, _, _) => format!("validation.{code}_equal"),
+            (_, true, true) => format!("validation.{code}_between"),
+            (_, true, false) => format!("validation.{code}_min"),
+            _ => format!("validation.{code}_max"),
+        },
+        code => format!("validation.{code}"),
+    };
+    i18n::text(&key)
+        .or_else(|| i18n::text("validation.invalid"))
+        .map(|template| i18n::format_named(template, param))
+        .unwrap_or(key)
+}
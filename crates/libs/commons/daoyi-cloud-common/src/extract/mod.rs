@@ -1,36 +1,55 @@
+mod valid;
 pub mod validations;
 
-use crate::error::{ApiError, format_validation_errors};
+pub use crate::auth::jwt::middleware::CurrentUser;
+pub use valid::{ValidJson, ValidPath, ValidQuery};
+
+use crate::error::ApiError;
+use crate::i18n;
+use crate::openapi::ValidateSchema;
+use salvo::http::ParseError;
 use salvo::prelude::*;
 use serde::de::DeserializeOwned;
 use validator::Validate;
 
 /// 从请求体提取并校验 JSON
-pub async fn extract_valid_json<T: DeserializeOwned + Validate + Send>(
+pub async fn extract_valid_json<T: DeserializeOwned + Validate + ValidateSchema + Send>(
     req: &mut Request,
     _depot: &mut Depot,
 ) -> Result<T, ApiError> {
     let body = req
         .payload()
         .await
-        .map_err(|e| ApiError::Validation(format!("请求体读取失败: {}", e)))?;
+        .map_err(|e| match e {
+            ParseError::PayloadTooLarge => ApiError::PayloadTooLarge,
+            e => ApiError::validation(i18n::t("request.body_read_failed", &[&e])),
+        })?;
     let data: T = serde_json::from_slice(body)
-        .map_err(|e| ApiError::Validation(format!("请求体解析失败: {}", e)))?;
-    data.validate()
-        .map_err(|e| ApiError::Validation(format_validation_errors(&e)))?;
+        .map_err(|e| ApiError::validation(i18n::t("request.body_parse_failed", &[&e])))?;
+    data.validate().map_err(|e| ApiError::invalid::<T>(&e))?;
     Ok(data)
 }
 
 /// 从查询参数提取并校验
-pub async fn extract_valid_query<T: DeserializeOwned + Validate + Send>(
+pub async fn extract_valid_query<T: DeserializeOwned + Validate + ValidateSchema + Send>(
     req: &mut Request,
     _depot: &mut Depot,
 ) -> Result<T, ApiError> {
     let query_str = req.uri().query().unwrap_or("");
     let data: T = serde_html_form::from_str(query_str)
-        .map_err(|e| ApiError::Validation(format!("查询参数解析失败: {}", e)))?;
-    data.validate()
-        .map_err(|e| ApiError::Validation(format_validation_errors(&e)))?;
+        .map_err(|e| ApiError::validation(i18n::t("request.query_parse_failed", &[&e])))?;
+    data.validate().map_err(|e| ApiError::invalid::<T>(&e))?;
+    Ok(data)
+}
+
+/// 从路径参数提取并校验，`T` 的字段对应路由中的参数名
+pub async fn extract_valid_path<T: DeserializeOwned + Validate + ValidateSchema + Send>(
+    req: &mut Request,
+    _depot: &mut Depot,
+) -> Result<T, ApiError> {
+    let data: T = salvo::serde::from_str_map(req.params().iter())
+        .map_err(|e| ApiError::validation(i18n::t("request.path_parse_failed", &[&e])))?;
+    data.validate().map_err(|e| ApiError::invalid::<T>(&e))?;
     Ok(data)
 }
 
@@ -40,5 +59,5 @@ pub fn extract_path_param<T: serde::de::DeserializeOwned + std::fmt::Debug>(
     name: &str,
 ) -> Result<T, ApiError> {
     req.param::<T>(name)
-        .ok_or_else(|| ApiError::Validation(format!("路径参数 {} 缺失或格式错误", name)))
+        .ok_or_else(|| ApiError::validation(i18n::t("request.path_param_invalid", &[&name])))
 }
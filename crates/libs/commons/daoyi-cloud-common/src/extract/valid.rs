@@ -1 +1,229 @@
+use crate::extract::{extract_valid_json, extract_valid_path, extract_valid_query};
+use crate::openapi::ValidateSchema;
+use salvo::extract::{Extractible, Metadata};
+use salvo::oapi::{
+    Components, Content, EndpointArgRegister, Operation, Parameter, ParameterIn, RefOr,
+    RequestBody, Required, Schema,
+};
+use salvo::prelude::*;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+use validator::Validate;
 
+macro_rules! valid_extractor {
+    ($(#[$meta:meta])* $name:ident, $extract:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name<T>(pub T);
+
+        impl<T> $name<T> {
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> Deref for $name<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> DerefMut for $name<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+
+        impl<'ex, T> Extractible<'ex> for $name<T>
+        where
+            T: DeserializeOwned + Validate + ValidateSchema + Send,
+        {
+            fn metadata() -> &'static Metadata {
+                static METADATA: Metadata = Metadata::new(stringify!($name));
+                &METADATA
+            }
+
+            async fn extract(
+                req: &'ex mut Request,
+                depot: &'ex mut Depot,
+            ) -> Result<Self, impl Writer + Send + Debug + 'static> {
+                $extract(req, depot).await.map(Self)
+            }
+        }
+    };
+}
+
+valid_extractor!(
+    /// 请求体 JSON，提取后执行 `validator` 校验，并在 OpenAPI 中登记请求体
+    ValidJson,
+    extract_valid_json
+);
+valid_extractor!(
+    /// 查询参数，提取后执行 `validator` 校验，并在 OpenAPI 中按字段登记查询参数
+    ValidQuery,
+    extract_valid_query
+);
+valid_extractor!(
+    /// 路径参数，`T` 的字段对应路由中的参数名，提取后执行 `validator` 校验，并在 OpenAPI 中登记路径参数
+    ValidPath,
+    extract_valid_path
+);
+
+impl<T: ValidateSchema> EndpointArgRegister for ValidJson<T> {
+    fn register(components: &mut Components, operation: &mut Operation, _arg: &str) {
+        let schema = T::register(components);
+        operation.request_body = Some(
+            RequestBody::new()
+                .add_content("application/json", Content::new(schema))
+                .required(Required::True),
+        );
+    }
+}
+
+impl<T: ValidateSchema> EndpointArgRegister for ValidQuery<T> {
+    fn register(components: &mut Components, operation: &mut Operation, _arg: &str) {
+        let schema = T::register(components);
+        register_parameters(components, operation, schema, ParameterIn::Query);
+    }
+}
+
+impl<T: ValidateSchema> EndpointArgRegister for ValidPath<T> {
+    fn register(components: &mut Components, operation: &mut Operation, _arg: &str) {
+        let schema = T::register(components);
+        register_parameters(components, operation, schema, ParameterIn::Path);
+    }
+}
+
+/// 将结构体 schema 的各字段登记为参数，展开 `$ref` 及 `#[serde(flatten)]` 生成的 `allOf`
+fn register_parameters(
+    components: &Components,
+    operation: &mut Operation,
+    schema: RefOr<Schema>,
+    parameter_in: ParameterIn,
+) {
+    let schema = match schema {
+        RefOr::Ref(reference) => {
+            let name = reference
+                .ref_location
+                .trim_start_matches("#/components/schemas/");
+            match components.schemas.get(name) {
+                Some(schema) => schema.clone(),
+                None => return,
+            }
+        }
+        schema => schema,
+    };
+    match schema {
+        RefOr::Type(Schema::Object(object)) => {
+            for (name, property) in object.properties {
+                let required = parameter_in == ParameterIn::Path || object.required.contains(&name);
+                let mut parameter = Parameter::new(&name)
+                    .parameter_in(parameter_in)
+                    .required(required);
+                if let RefOr::Type(Schema::Object(property)) = &property
+                    && let Some(description) = &property.description
+                {
+                    parameter = parameter.description(description);
+                }
+                operation.parameters.insert(parameter.schema(property));
+            }
+        }
+        RefOr::Type(Schema::AllOf(all_of)) => {
+            for item in all_of.items {
+                register_parameters(components, operation, item, parameter_in);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pojo::pagination::PageParam;
+    use salvo::oapi::{OpenApi, ToSchema, endpoint};
+    use salvo::test::{ResponseExt, TestClient};
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    #[derive(Debug, Deserialize, Validate, ToSchema, ValidateSchema)]
+    #[serde(rename_all = "camelCase")]
+    struct OrderQuery {
+        /// 订单状态
+        status: Option<String>,
+        #[serde(flatten)]
+        #[validate(nested)]
+        pagination: PageParam,
+    }
+
+    #[derive(Debug, Deserialize, Validate, ToSchema, ValidateSchema)]
+    struct OrderPath {
+        #[validate(range(min = 1))]
+        id: i64,
+    }
+
+    #[endpoint]
+    async fn orders(path: ValidPath<OrderPath>, query: ValidQuery<OrderQuery>) -> String {
+        format!(
+            "{}:{}:{}",
+            path.id,
+            query.status.as_deref().unwrap_or_default(),
+            query.pagination.page_size
+        )
+    }
+
+    #[tokio::test]
+    async fn test_valid_extractors() {
+        let router = Router::with_path("users/{id}/orders").get(orders);
+        let doc = serde_json::to_value(OpenApi::new("test", "1.0").merge_router(&router)).unwrap();
+        let parameters = &doc["paths"]["/users/{id}/orders"]["get"]["parameters"];
+        let names: Vec<_> = parameters
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| {
+                format!(
+                    "{}:{}",
+                    p["in"].as_str().unwrap(),
+                    p["name"].as_str().unwrap()
+                )
+            })
+            .collect();
+        assert_eq!(
+            names,
+            ["path:id", "query:pageNo", "query:pageSize", "query:status"]
+        );
+        assert_eq!(parameters[3]["description"], "订单状态");
+        assert_eq!(parameters[0]["schema"]["minimum"], 1);
+
+        let service = Service::new(router);
+        let mut res =
+            TestClient::get("http://127.0.0.1:8080/users/7/orders?status=paid&pageSize=20")
+                .send(&service)
+                .await;
+        assert_eq!(res.take_string().await.unwrap(), "7:paid:20");
+
+        let mut res = TestClient::get("http://127.0.0.1:8080/users/0/orders?pageSize=500")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+        let body: Value = res.take_json().await.unwrap();
+        let fields: Vec<_> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["field"].as_str().unwrap())
+            .collect();
+        assert_eq!(fields, ["id"]);
+
+        // flatten 字段的错误使用查询参数名
+        let mut res = TestClient::get("http://127.0.0.1:8080/users/7/orders?pageSize=500")
+            .send(&service)
+            .await;
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["data"][0]["field"], "pageSize");
+    }
+}
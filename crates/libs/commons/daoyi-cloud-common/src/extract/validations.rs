@@ -1,17 +1,25 @@
-use crate::constants::global_values::MOBILE_PHONE_REGEX;
+use crate::constants::global_values::{
+    CREDIT_CODE_REGEX, ID_CARD_REGEX, MOBILE_PHONE_REGEX, PLATE_NUMBER_REGEX, POSTAL_CODE_REGEX,
+};
+use chrono::{Local, NaiveDate};
+use sea_orm::ActiveEnum;
+use serde::Serialize;
 use std::borrow::Cow;
-use validator::ValidationError;
+use validator::{ValidateEmail, ValidateUrl, ValidationError};
+
+/// 每页条数上限
+pub const MAX_PAGE_SIZE: u64 = 200;
 
 pub fn validate_page_size(page_size: u64) -> Result<(), validator::ValidationError> {
     match page_size {
         s if s < 1 => {
-            let mut err = validator::ValidationError::new("page_size_range");
-            err.message = Some("每页条数最小值为 1".into());
+            let mut err = build_validation_error("page_size_min");
+            err.add_param(Cow::from("min"), &1);
             Err(err)
         }
-        s if s > 200 => {
-            let mut err = validator::ValidationError::new("page_size_range");
-            err.message = Some("每页条数最大值为 200".into());
+        s if s > MAX_PAGE_SIZE => {
+            let mut err = build_validation_error("page_size_max");
+            err.add_param(Cow::from("max"), &MAX_PAGE_SIZE);
             Err(err)
         }
         _ => Ok(()),
@@ -22,14 +30,229 @@ pub fn validate_mobile_phone(value: &str) -> Result<(), validator::ValidationErr
     if MOBILE_PHONE_REGEX.is_match(value) {
         Ok(())
     } else {
-        Err(build_validation_error("手机号格式不正确"))
+        Err(build_validation_error("mobile_phone"))
+    }
+}
+
+/// 18 位居民身份证号码：格式、出生日期及 GB 11643 校验码
+pub fn validate_id_card(value: &str) -> Result<(), validator::ValidationError> {
+    const WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+    const CHECK_CODES: &[u8; 11] = b"10X98765432";
+    let valid = ID_CARD_REGEX.is_match(value)
+        && NaiveDate::parse_from_str(&value[6..14], "%Y%m%d")
+            .is_ok_and(|birthday| birthday <= today())
+        && {
+            let sum: u32 = value
+                .bytes()
+                .zip(WEIGHTS)
+                .map(|(digit, weight)| u32::from(digit - b'0') * weight)
+                .sum();
+            CHECK_CODES[(sum % 11) as usize] == value.as_bytes()[17].to_ascii_uppercase()
+        };
+    if valid {
+        Ok(())
+    } else {
+        Err(build_validation_error("id_card"))
+    }
+}
+
+/// 统一社会信用代码：格式及 GB 32100 校验码
+pub fn validate_credit_code(value: &str) -> Result<(), validator::ValidationError> {
+    const CHARSET: &[u8; 31] = b"0123456789ABCDEFGHJKLMNPQRTUWXY";
+    const WEIGHTS: [usize; 17] = [
+        1, 3, 9, 27, 19, 26, 16, 17, 20, 29, 25, 13, 8, 24, 10, 30, 28,
+    ];
+    let index = |c: u8| CHARSET.iter().position(|&x| x == c).unwrap_or_default();
+    let valid = CREDIT_CODE_REGEX.is_match(value) && {
+        let sum: usize = value
+            .bytes()
+            .zip(WEIGHTS)
+            .map(|(c, weight)| index(c) * weight)
+            .sum();
+        CHARSET[(31 - sum % 31) % 31] == value.as_bytes()[17]
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(build_validation_error("credit_code"))
+    }
+}
+
+/// 电子邮箱，规则同 `#[validate(email)]`，便于在自定义校验中复用
+pub fn validate_email(value: &str) -> Result<(), validator::ValidationError> {
+    if value.validate_email() {
+        Ok(())
+    } else {
+        Err(build_validation_error("email"))
+    }
+}
+
+/// http / https 地址
+pub fn validate_http_url(value: &str) -> Result<(), validator::ValidationError> {
+    let scheme = value.split_once("://").map(|(scheme, _)| scheme);
+    if value.validate_url()
+        && scheme.is_some_and(|s| s.eq_ignore_ascii_case("http") || s.eq_ignore_ascii_case("https"))
+    {
+        Ok(())
+    } else {
+        Err(build_validation_error("url"))
+    }
+}
+
+/// 中国大陆邮政编码
+pub fn validate_postal_code(value: &str) -> Result<(), validator::ValidationError> {
+    if POSTAL_CODE_REGEX.is_match(value) {
+        Ok(())
+    } else {
+        Err(build_validation_error("postal_code"))
+    }
+}
+
+/// 中国大陆机动车号牌，含新能源号牌
+pub fn validate_plate_number(value: &str) -> Result<(), validator::ValidationError> {
+    if PLATE_NUMBER_REGEX.is_match(value) {
+        Ok(())
+    } else {
+        Err(build_validation_error("plate_number"))
+    }
+}
+
+/// 不晚于今天（本地时区），如出生日期
+pub fn validate_not_future(value: &NaiveDate) -> Result<(), validator::ValidationError> {
+    if *value <= today() {
+        Ok(())
+    } else {
+        Err(build_validation_error("not_future"))
     }
 }
 
-fn build_validation_error(message: &'static str) -> validator::ValidationError {
+/// 不早于今天（本地时区），如预约日期
+pub fn validate_not_past(value: &NaiveDate) -> Result<(), validator::ValidationError> {
+    if *value >= today() {
+        Ok(())
+    } else {
+        Err(build_validation_error("not_past"))
+    }
+}
+
+/// 取值为枚举 `E` 的数据库值之一，用于以原始值接收的枚举字段，如
+/// `#[validate(custom(function = "validate_enum::<Gender>"))]`；可选值写入参数 `values`
+pub fn validate_enum<E>(value: &E::Value) -> Result<(), validator::ValidationError>
+where
+    E: ActiveEnum,
+    E::Value: PartialEq + Serialize,
+{
+    let values = E::values();
+    if values.contains(value) {
+        Ok(())
+    } else {
+        let mut err = build_validation_error("enum");
+        err.add_param(Cow::from("values"), &values);
+        Err(err)
+    }
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// 不带 message 的校验错误，提示信息按错误码取 `validation.<code>`
+fn build_validation_error(code: &'static str) -> validator::ValidationError {
     ValidationError {
-        code: Cow::from("invalid"),
-        message: Some(Cow::from(message)),
+        code: Cow::from(code),
+        message: None,
         params: Default::default(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::enumeration::Gender;
+    use chrono::Days;
+
+    #[test]
+    fn test_id_card() {
+        assert!(validate_id_card("11010519491231002X").is_ok());
+        assert!(validate_id_card("11010519491231002x").is_ok());
+        assert!(validate_id_card("440524188001010014").is_ok());
+        // 校验码错误
+        assert!(validate_id_card("110105194912310021").is_err());
+        // 出生日期不存在
+        assert!(validate_id_card("110105194902300021").is_err());
+        assert!(validate_id_card("1101051949123100").is_err());
+        assert_eq!(validate_id_card("").unwrap_err().code, "id_card");
+    }
+
+    #[test]
+    fn test_credit_code() {
+        assert!(validate_credit_code("91350100M000100Y43").is_ok());
+        assert!(validate_credit_code("91440300708461136T").is_ok());
+        assert!(validate_credit_code("91350100M000100Y44").is_err());
+        assert!(validate_credit_code("91350100m000100Y43").is_err());
+        assert_eq!(validate_credit_code("").unwrap_err().code, "credit_code");
+    }
+
+    #[test]
+    fn test_email_and_url() {
+        assert!(validate_email("dev@example.com").is_ok());
+        assert!(validate_email("dev@").is_err());
+        assert!(validate_http_url("https://example.com/path?q=1").is_ok());
+        assert!(validate_http_url("HTTP://example.com").is_ok());
+        assert!(validate_http_url("ftp://example.com").is_err());
+        assert!(validate_http_url("example.com").is_err());
+    }
+
+    #[test]
+    fn test_postal_code_and_plate_number() {
+        assert!(validate_postal_code("100080").is_ok());
+        assert!(validate_postal_code("1000800").is_err());
+        assert!(validate_plate_number("京A12345").is_ok());
+        assert!(validate_plate_number("京A1234").is_err());
+    }
+
+    #[test]
+    fn test_date_relative_to_today() {
+        let today = today();
+        let tomorrow = today.checked_add_days(Days::new(1)).unwrap();
+        let yesterday = today.checked_sub_days(Days::new(1)).unwrap();
+        assert!(validate_not_future(&today).is_ok());
+        assert!(validate_not_future(&yesterday).is_ok());
+        assert_eq!(
+            validate_not_future(&tomorrow).unwrap_err().code,
+            "not_future"
+        );
+        assert!(validate_not_past(&today).is_ok());
+        assert!(validate_not_past(&tomorrow).is_ok());
+        assert_eq!(validate_not_past(&yesterday).unwrap_err().code, "not_past");
+    }
+
+    #[test]
+    fn test_enum() {
+        assert!(validate_enum::<Gender>(&String::from("male")).is_ok());
+        let err = validate_enum::<Gender>(&String::from("unknown")).unwrap_err();
+        assert_eq!(err.code, "enum");
+        assert_eq!(err.params["values"], serde_json::json!(["male", "female"]));
+    }
+
+    #[derive(validator::Validate, salvo::oapi::ToSchema, crate::openapi::ValidateSchema)]
+    struct Params {
+        #[validate(custom(function = "validate_enum::<Gender>"))]
+        gender: String,
+        #[validate(custom(function = "validate_id_card", message = "证件号码有误"))]
+        id_card: String,
+    }
+
+    #[test]
+    fn test_messages() {
+        use validator::Validate;
+        let params = Params {
+            gender: String::from("unknown"),
+            id_card: String::from("110105194912310021"),
+        };
+        let errors = crate::error::field_errors_of::<Params>(&params.validate().unwrap_err());
+        assert_eq!(errors[0].message, "必须是 male, female 之一");
+        // message 覆盖默认提示
+        assert_eq!(errors[1].message, "证件号码有误");
+    }
+}
@@ -0,0 +1,325 @@
+use crate::conf::HealthConfig;
+use crate::conf::health::DiskSpaceConfig;
+use crate::{db, shutdown};
+use salvo::async_trait;
+use salvo::prelude::*;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+static INDICATORS: RwLock<Vec<Arc<dyn HealthIndicator>>> = RwLock::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HealthStatus {
+    Up,
+    Down,
+    OutOfService,
+}
+
+/// 单个组件的健康状态
+#[derive(Debug, Clone, Serialize)]
+pub struct Health {
+    pub status: HealthStatus,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub details: Map<String, Value>,
+}
+
+impl Health {
+    pub fn up() -> Self {
+        Self {
+            status: HealthStatus::Up,
+            details: Map::new(),
+        }
+    }
+
+    pub fn down(error: impl ToString) -> Self {
+        Self::up()
+            .status(HealthStatus::Down)
+            .detail("error", error.to_string())
+    }
+
+    pub fn status(mut self, status: HealthStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn detail(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.details.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// 健康指示器，业务模块可实现并通过 [`register`] 注册自定义检查
+#[async_trait]
+pub trait HealthIndicator: Send + Sync + 'static {
+    /// 组件名称
+    fn name(&self) -> &str;
+
+    /// 是否参与就绪检查（`/health/ready`）
+    fn readiness(&self) -> bool {
+        true
+    }
+
+    async fn health(&self) -> Health;
+}
+
+pub fn register(indicator: impl HealthIndicator) {
+    INDICATORS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(indicator));
+}
+
+/// 注册内置的健康指示器
+pub fn init(config: &HealthConfig) {
+    register(DbHealthIndicator);
+    if config.disk_space().enabled() {
+        register(DiskSpaceHealthIndicator::new(config.disk_space()));
+    }
+}
+
+/// 汇总后的健康报告
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, Health>,
+}
+
+/// 并发执行健康指示器，任一组件非 UP 时整体为 DOWN
+pub async fn check(readiness_only: bool, timeout: Duration) -> HealthReport {
+    let indicators: Vec<_> = INDICATORS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|indicator| !readiness_only || indicator.readiness())
+        .cloned()
+        .collect();
+    let mut tasks = JoinSet::new();
+    for indicator in indicators {
+        tasks.spawn(async move {
+            let health = tokio::time::timeout(timeout, indicator.health())
+                .await
+                .unwrap_or_else(|_| Health::down(format!("timed out after {timeout:?}")));
+            (indicator.name().to_string(), health)
+        });
+    }
+    let mut components = BTreeMap::new();
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok((name, health)) => {
+                components.insert(name, health);
+            }
+            Err(e) => tracing::error!("Health indicator panicked: {}", e),
+        }
+    }
+    let status = if components.values().all(|h| h.status == HealthStatus::Up) {
+        HealthStatus::Up
+    } else {
+        HealthStatus::Down
+    };
+    HealthReport { status, components }
+}
+
+/// 健康检查路由，挂载在 JWT 认证之外
+pub fn create_router(config: &'static HealthConfig) -> Router {
+    Router::new()
+        .push(Router::with_path("actuator/health").get(HealthHandler::new(config, Probe::Health)))
+        .push(Router::with_path("health/live").get(HealthHandler::new(config, Probe::Live)))
+        .push(Router::with_path("health/ready").get(HealthHandler::new(config, Probe::Ready)))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Probe {
+    /// 全部组件
+    Health,
+    /// 存活检查，进程能响应即为 UP
+    Live,
+    /// 就绪检查，停机过程中为 OUT_OF_SERVICE
+    Ready,
+}
+
+struct HealthHandler {
+    config: &'static HealthConfig,
+    probe: Probe,
+}
+
+impl HealthHandler {
+    fn new(config: &'static HealthConfig, probe: Probe) -> Self {
+        Self { config, probe }
+    }
+}
+
+#[handler]
+impl HealthHandler {
+    async fn handle(&self, res: &mut Response) {
+        let mut report = match self.probe {
+            Probe::Live => HealthReport {
+                status: HealthStatus::Up,
+                components: BTreeMap::new(),
+            },
+            Probe::Health => check(false, self.config.timeout()).await,
+            Probe::Ready => check(true, self.config.timeout()).await,
+        };
+        if !matches!(self.probe, Probe::Live) && shutdown::is_shutting_down() {
+            report.status = HealthStatus::OutOfService;
+        }
+        if !self.config.show_details() {
+            report.components.clear();
+        }
+        if report.status != HealthStatus::Up {
+            res.status_code(StatusCode::SERVICE_UNAVAILABLE);
+        }
+        res.render(Json(report));
+    }
+}
+
+/// 数据库连接检查
+pub struct DbHealthIndicator;
+
+#[async_trait]
+impl HealthIndicator for DbHealthIndicator {
+    fn name(&self) -> &str {
+        "db"
+    }
+
+    async fn health(&self) -> Health {
+        let Some(conn) = db::try_get() else {
+            return Health::down("database connection not initialized");
+        };
+        let start = Instant::now();
+        let backend = format!("{:?}", conn.get_database_backend());
+        match conn.ping().await {
+            Ok(()) => Health::up()
+                .detail("database", backend)
+                .detail("latency_ms", start.elapsed().as_millis() as u64),
+            Err(e) => Health::down(e).detail("database", backend),
+        }
+    }
+}
+
+/// 磁盘剩余空间检查
+pub struct DiskSpaceHealthIndicator {
+    path: String,
+    threshold: u64,
+}
+
+impl DiskSpaceHealthIndicator {
+    pub fn new(config: &DiskSpaceConfig) -> Self {
+        Self {
+            path: config.path().to_string(),
+            threshold: config.threshold(),
+        }
+    }
+}
+
+#[async_trait]
+impl HealthIndicator for DiskSpaceHealthIndicator {
+    fn name(&self) -> &str {
+        "disk_space"
+    }
+
+    fn readiness(&self) -> bool {
+        false
+    }
+
+    async fn health(&self) -> Health {
+        match disk_space(&self.path) {
+            Ok((total, free)) => {
+                let status = if free >= self.threshold {
+                    HealthStatus::Up
+                } else {
+                    HealthStatus::Down
+                };
+                Health::up()
+                    .status(status)
+                    .detail("path", self.path.as_str())
+                    .detail("total", total)
+                    .detail("free", free)
+                    .detail("threshold", self.threshold)
+            }
+            Err(e) => Health::down(e).detail("path", self.path.as_str()),
+        }
+    }
+}
+
+/// 返回路径所在文件系统的（总空间, 可用空间）字节数
+#[cfg(unix)]
+fn disk_space(path: &str) -> std::io::Result<(u64, u64)> {
+    let path = std::ffi::CString::new(path)?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: path 为合法的 C 字符串，stat 由 statvfs 成功返回后才读取
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        stat.assume_init()
+    };
+    let block_size = stat.f_frsize as u64;
+    Ok((
+        stat.f_blocks as u64 * block_size,
+        stat.f_bavail as u64 * block_size,
+    ))
+}
+
+#[cfg(not(unix))]
+fn disk_space(_path: &str) -> std::io::Result<(u64, u64)> {
+    Err(std::io::Error::other("disk space check is not supported"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+
+    struct Fixed(&'static str, bool);
+
+    #[async_trait]
+    impl HealthIndicator for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn readiness(&self) -> bool {
+            self.1
+        }
+
+        async fn health(&self) -> Health {
+            Health::down("unavailable")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoints() {
+        let config: &'static HealthConfig = Box::leak(Box::default());
+        init(config);
+        register(Fixed("cache", false));
+        let service = Service::new(create_router(config));
+
+        let mut res = TestClient::get("http://127.0.0.1:8080/actuator/health")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::SERVICE_UNAVAILABLE));
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["status"], "DOWN");
+        assert_eq!(body["components"]["db"]["status"], "DOWN");
+        assert_eq!(body["components"]["disk_space"]["status"], "UP");
+        assert_eq!(body["components"]["cache"]["details"]["error"], "unavailable");
+
+        let mut res = TestClient::get("http://127.0.0.1:8080/health/ready")
+            .send(&service)
+            .await;
+        let body: Value = res.take_json().await.unwrap();
+        assert!(body["components"]["cache"].is_null());
+        assert!(body["components"]["disk_space"].is_null());
+
+        let res = TestClient::get("http://127.0.0.1:8080/health/live")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+    }
+}
@@ -0,0 +1,386 @@
+use crate::auth::jwt::Principal;
+use crate::auth::jwt::middleware::principal;
+use crate::conf::I18nConfig;
+use anyhow::Context;
+use config::{Config, FileFormat};
+use salvo::http::HeaderValue;
+use salvo::http::header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE};
+use salvo::prelude::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+/// 内置消息，`(语言, YAML)`
+const BUILTIN_BUNDLES: &[(&str, &str)] = &[
+    ("zh-CN", include_str!("../../resources/i18n/zh-CN.yaml")),
+    ("en-US", include_str!("../../resources/i18n/en-US.yaml")),
+];
+
+static BUNDLES: OnceLock<Bundles> = OnceLock::new();
+static PREFERENCE: OnceLock<Arc<dyn LocalePreference>> = OnceLock::new();
+
+tokio::task_local! {
+    static CURRENT_LOCALE: &'static str;
+}
+
+struct Bundles {
+    /// 支持的语言，首个为默认语言
+    locales: Vec<String>,
+    messages: HashMap<String, HashMap<String, String>>,
+}
+
+impl Bundles {
+    fn default_locale(&self) -> &str {
+        &self.locales[0]
+    }
+
+    fn builtin() -> Self {
+        let mut messages = HashMap::new();
+        for (locale, yaml) in BUILTIN_BUNDLES {
+            let bundle = parse(config::File::from_str(yaml, FileFormat::Yaml))
+                .expect("builtin message bundle is valid");
+            messages.insert(locale.to_string(), bundle);
+        }
+        Self {
+            locales: BUILTIN_BUNDLES
+                .iter()
+                .map(|(locale, _)| locale.to_string())
+                .collect(),
+            messages,
+        }
+    }
+
+    fn load(config: &I18nConfig) -> anyhow::Result<Self> {
+        let mut builtin = Self::builtin().messages;
+        let mut messages = HashMap::new();
+        for locale in config.locales() {
+            let mut bundle = builtin.remove(locale).unwrap_or_default();
+            let path = Path::new(config.path()).join(format!("{locale}.yaml"));
+            if path.is_file() {
+                let file = config::File::from(path.as_path()).format(FileFormat::Yaml);
+                let overrides = parse(file)
+                    .with_context(|| format!("Failed to load messages {}", path.display()))?;
+                bundle.extend(overrides);
+            } else if bundle.is_empty() {
+                tracing::warn!(
+                    "No messages for locale `{}`, falling back to `{}`",
+                    locale,
+                    config.default_locale()
+                );
+            }
+            messages.insert(locale.to_string(), bundle);
+        }
+        Ok(Self {
+            locales: config.locales().into_iter().map(String::from).collect(),
+            messages,
+        })
+    }
+
+    fn text(&self, locale: &str, key: &str) -> Option<&str> {
+        [locale, self.default_locale()]
+            .into_iter()
+            .find_map(|locale| self.messages.get(locale)?.get(key))
+            .map(String::as_str)
+    }
+
+    /// 按语言标签匹配支持的语言：先完全匹配，再按主语言匹配（如 `en-GB` 匹配 `en-US`）
+    fn matching(&self, tag: &str) -> Option<&str> {
+        let primary = |tag: &str| {
+            tag.split('-')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase()
+        };
+        self.locales
+            .iter()
+            .find(|locale| locale.eq_ignore_ascii_case(tag))
+            .or_else(|| {
+                self.locales
+                    .iter()
+                    .find(|locale| primary(locale) == primary(tag))
+            })
+            .map(String::as_str)
+    }
+
+    /// 按 `Accept-Language` 的权重依次匹配
+    fn negotiate(&self, accept_language: &str) -> Option<&str> {
+        let mut tags: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse().ok())?;
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+        tags.into_iter().find_map(|(tag, _)| match tag {
+            "*" => Some(self.default_locale()),
+            tag => self.matching(tag),
+        })
+    }
+}
+
+/// 解析消息文件，嵌套的键以 `.` 连接
+fn parse<T>(source: T) -> anyhow::Result<HashMap<String, String>>
+where
+    T: config::Source + Send + Sync + 'static,
+{
+    let value: Value = Config::builder()
+        .add_source(source)
+        .build()?
+        .try_deserialize()?;
+    let mut messages = HashMap::new();
+    flatten(String::new(), value, &mut messages);
+    Ok(messages)
+}
+
+fn flatten(prefix: String, value: Value, messages: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(key, value, messages);
+            }
+        }
+        Value::String(text) => {
+            messages.insert(prefix, text);
+        }
+        Value::Null => {}
+        other => {
+            messages.insert(prefix, other.to_string());
+        }
+    }
+}
+
+fn bundles() -> &'static Bundles {
+    BUNDLES.get_or_init(Bundles::builtin)
+}
+
+/// 加载配置的语言及消息文件，未调用时只使用内置消息
+pub fn init(config: &I18nConfig) -> anyhow::Result<()> {
+    let bundles = Bundles::load(config)?;
+    tracing::info!("Loaded messages for locales {:?}", bundles.locales);
+    if BUNDLES.set(bundles).is_err() {
+        tracing::warn!("Message bundles are already initialized");
+    }
+    Ok(())
+}
+
+/// 用户语言偏好（如用户设置中的语言），优先于 `Accept-Language`，通过 [`set_preference`] 注册
+#[salvo::async_trait]
+pub trait LocalePreference: Send + Sync + 'static {
+    /// 返回语言标签，`None` 表示没有偏好。语言中间件先于认证中间件执行，
+    /// `principal` 为已校验令牌的当前用户，未登录或令牌无效时为 `None`
+    async fn locale(&self, req: &Request, principal: Option<&Principal>) -> Option<String>;
+}
+
+/// 注册用户语言偏好，需在服务启动前调用
+pub fn set_preference(preference: impl LocalePreference) {
+    if PREFERENCE.set(Arc::new(preference)).is_err() {
+        tracing::warn!("Locale preference is already set");
+    }
+}
+
+/// 默认语言
+pub fn default_locale() -> &'static str {
+    bundles().default_locale()
+}
+
+/// 当前请求的语言，请求之外为默认语言
+pub fn current_locale() -> &'static str {
+    CURRENT_LOCALE
+        .try_with(|locale| *locale)
+        .unwrap_or_else(|_| default_locale())
+}
+
+/// 解析请求语言：用户偏好优先，其次 `Accept-Language`，都不支持时为默认语言
+pub async fn resolve(req: &Request) -> &'static str {
+    resolve_with(req, PREFERENCE.get().map(Arc::as_ref), bundles()).await
+}
+
+async fn resolve_with(
+    req: &Request,
+    preference: Option<&dyn LocalePreference>,
+    bundles: &'static Bundles,
+) -> &'static str {
+    if let Some(preference) = preference
+        && let Some(locale) = preference.locale(req, principal(req).as_ref()).await
+        && let Some(locale) = bundles.matching(&locale)
+    {
+        return locale;
+    }
+    req.headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| bundles.negotiate(value))
+        .unwrap_or_else(|| bundles.default_locale())
+}
+
+/// 在指定语言下执行
+pub async fn scope<F: Future>(locale: &'static str, f: F) -> F::Output {
+    CURRENT_LOCALE.scope(locale, f).await
+}
+
+/// 当前语言的消息，缺失时回退到默认语言
+pub fn text(key: &str) -> Option<&'static str> {
+    bundles().text(current_locale(), key)
+}
+
+/// 当前语言的消息，`args` 依次填充 `{}`；消息缺失时返回 `key`
+pub fn t(key: &str, args: &[&dyn Display]) -> String {
+    match text(key) {
+        Some(template) => format(template, args),
+        None => key.to_string(),
+    }
+}
+
+/// 依次用参数替换模板中的 `{}`，多余的参数忽略，缺少参数的占位符保留原样
+pub fn format(template: &str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(index) = rest.find("{}") {
+        message.push_str(&rest[..index]);
+        match args.next() {
+            Some(arg) => {
+                let _ = write!(message, "{arg}");
+            }
+            None => message.push_str("{}"),
+        }
+        rest = &rest[index + 2..];
+    }
+    message.push_str(rest);
+    message
+}
+
+/// 用命名参数替换模板中的 `{name}`，`param` 返回 `None` 的占位符保留原样
+pub fn format_named(template: &str, param: impl Fn(&str) -> Option<String>) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        message.push_str(&rest[..start]);
+        let name = &rest[start + 1..end];
+        match param(name) {
+            Some(value) => message.push_str(&value),
+            None => message.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    message.push_str(rest);
+    message
+}
+
+/// 语言中间件：解析请求语言，请求处理期间的消息都使用该语言，并返回 `Content-Language`。
+/// 需加在 Service 上，紧随访问日志之后。
+pub struct LocaleHandler;
+
+#[handler]
+impl LocaleHandler {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let locale = resolve(req).await;
+        scope(locale, ctrl.call_next(req, depot, res)).await;
+        if let Ok(value) = HeaderValue::from_str(locale) {
+            res.headers_mut().insert(CONTENT_LANGUAGE, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        let bundles = Bundles::builtin();
+        assert_eq!(bundles.negotiate("en-US,en;q=0.9"), Some("en-US"));
+        assert_eq!(
+            bundles.negotiate("fr;q=1, en-GB;q=0.8, zh;q=0.9"),
+            Some("zh-CN")
+        );
+        assert_eq!(bundles.negotiate("zh-CN;q=0, en"), Some("en-US"));
+        assert_eq!(bundles.negotiate("*"), Some("zh-CN"));
+        assert_eq!(bundles.negotiate("fr, de"), None);
+    }
+
+    #[tokio::test]
+    async fn test_fallback() {
+        let text = scope("en-US", async { t("request.path_param_invalid", &[&"id"]) }).await;
+        assert_eq!(text, "Path parameter id is missing or invalid");
+        // 英文缺失的键回退到默认语言，都缺失时返回键
+        let mut bundles = Bundles::builtin();
+        bundles
+            .messages
+            .get_mut("en-US")
+            .unwrap()
+            .remove("auth.credential_required");
+        assert_eq!(
+            bundles.text("en-US", "auth.credential_required"),
+            Some("访问需要认证")
+        );
+        assert_eq!(t("missing.key", &[]), "missing.key");
+        let param = |name: &str| (name == "min").then(|| String::from("1"));
+        assert_eq!(
+            format_named("长度必须在 {min} 到 {max} 之间", param),
+            "长度必须在 1 到 {max} 之间"
+        );
+    }
+
+    struct UserPreference;
+
+    #[salvo::async_trait]
+    impl LocalePreference for UserPreference {
+        async fn locale(&self, _req: &Request, principal: Option<&Principal>) -> Option<String> {
+            principal
+                .filter(|principal| principal.id == 1)
+                .map(|_| String::from("en-US"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preference_principal() {
+        use crate::auth::jwt::default_jwt;
+        use salvo::test::TestClient;
+
+        let bundles: &'static Bundles = Box::leak(Box::new(Bundles::builtin()));
+        let principal = Principal {
+            tenant_id: 1,
+            id: 1,
+            name: String::from("admin"),
+        };
+        let token = default_jwt().encode(principal).unwrap();
+        // 语言中间件先于认证中间件执行，偏好仍能取得已校验的用户
+        let req = TestClient::get("http://127.0.0.1/")
+            .bearer_auth(&token)
+            .build();
+        assert_eq!(
+            resolve_with(&req, Some(&UserPreference), bundles).await,
+            "en-US"
+        );
+        let req = TestClient::get("http://127.0.0.1/")
+            .bearer_auth("invalid")
+            .build();
+        assert_eq!(
+            resolve_with(&req, Some(&UserPreference), bundles).await,
+            "zh-CN"
+        );
+    }
+}
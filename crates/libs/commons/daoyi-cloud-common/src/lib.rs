@@ -1,3 +1,6 @@
+// 使 `#[derive(ValidateSchema)]` 生成的 `::daoyi_cloud_common` 路径在本 crate 内可用
+extern crate self as daoyi_cloud_common;
+
 pub mod app;
 pub mod auth;
 pub mod conf;
@@ -5,8 +8,14 @@ pub mod constants;
 pub mod db;
 pub mod error;
 pub mod extract;
+pub mod health;
+pub mod i18n;
 pub mod logger;
+pub mod metrics;
+pub mod openapi;
 pub mod pojo;
 pub mod response;
 pub mod server;
+pub mod shutdown;
+pub mod telemetry;
 pub mod utils;
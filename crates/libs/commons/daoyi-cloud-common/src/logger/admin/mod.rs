@@ -0,0 +1,191 @@
+//! 运行时查看、调整日志过滤指令的管理端点，需配置 `logging.admin.auth` 访问保护。
+
+use crate::auth::credential::CredentialGuard;
+use crate::conf::CredentialConfig;
+use crate::conf::logging::LogAdminConfig;
+use crate::error::ApiError;
+use crate::response::write_error_response;
+use crate::{i18n, logger};
+use salvo::prelude::*;
+use serde::{Deserialize, Deserializer};
+use std::time::Duration;
+
+/// 调整日志过滤指令的请求参数
+#[derive(Debug, Deserialize)]
+struct LevelParams {
+    /// `EnvFilter` 指令，如 `info,daoyi_cloud_common=debug`
+    directives: String,
+    /// 生效时间（秒），缺省为 `max_duration`，到期后恢复为启动时的指令；
+    /// 显式传 `null` 或 `0` 时永久生效，直至再次调整或 `DELETE` 恢复
+    #[serde(default, deserialize_with = "deserialize_present")]
+    duration: Option<Option<u64>>,
+}
+
+/// 区分字段缺省（`None`）与显式传 `null`（`Some(None)`）
+fn deserialize_present<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Option<u64>>, D::Error> {
+    Option::deserialize(deserializer).map(Some)
+}
+
+/// `GET` 查看、`PUT` 调整、`DELETE` 恢复日志过滤指令
+pub fn create_router(config: &LogAdminConfig, auth: &CredentialConfig) -> Router {
+    Router::with_path(config.path().trim_start_matches('/'))
+        .hoop(CredentialGuard::new(auth.clone(), "loggers"))
+        .get(get_levels)
+        .put(SetLevels {
+            max_duration: config.max_duration(),
+        })
+        .delete(reset_levels)
+}
+
+#[handler]
+async fn get_levels(res: &mut Response) {
+    match logger::levels() {
+        Some(levels) => crate::success!(res, levels),
+        None => write_error_response(res, ApiError::NotFound),
+    }
+}
+
+struct SetLevels {
+    max_duration: Duration,
+}
+
+#[handler]
+impl SetLevels {
+    async fn handle(&self, req: &mut Request, res: &mut Response) {
+        let params = match req.parse_json::<LevelParams>().await {
+            Ok(params) => params,
+            Err(e) => {
+                write_error_response(res, ApiError::validation(e.to_string()));
+                return;
+            }
+        };
+        let revert_after = match params.duration {
+            None => Some(self.max_duration),
+            Some(None | Some(0)) => None,
+            Some(Some(secs)) => Some(Duration::from_secs(secs)),
+        };
+        if revert_after.is_some_and(|after| after > self.max_duration) {
+            write_error_response(
+                res,
+                ApiError::validation(i18n::t(
+                    "logger.invalid_duration",
+                    &[&self.max_duration.as_secs()],
+                )),
+            );
+            return;
+        }
+        match logger::set_levels(&params.directives, revert_after) {
+            Ok(levels) => crate::success!(res, levels),
+            Err(e) => write_error_response(res, ApiError::validation(format!("{e:#}"))),
+        }
+    }
+}
+
+#[handler]
+async fn reset_levels(res: &mut Response) {
+    match logger::reset_levels() {
+        Ok(levels) => crate::success!(res, levels),
+        Err(e) => write_error_response(res, ApiError::Internal(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+    use serde_json::{Value, json};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::{EnvFilter, Layer, reload};
+
+    /// 统计通过过滤的事件数
+    struct CountLayer(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> Layer<S> for CountLayer {
+        fn on_event(&self, _event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_change_and_revert_levels() {
+        let (filter, handle) = reload::Layer::new(EnvFilter::new("info"));
+        let events = Arc::new(AtomicUsize::new(0));
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(CountLayer(events.clone()));
+        let _guard = tracing::subscriber::set_default(subscriber);
+        logger::install_levels(handle);
+        let debug_events = || {
+            let before = events.load(Ordering::SeqCst);
+            tracing::debug!("probe");
+            events.load(Ordering::SeqCst) - before
+        };
+        assert_eq!(debug_events(), 0);
+
+        let config: LogAdminConfig = serde_json::from_value(json!({
+            "enabled": true,
+            "max_duration": 60,
+        }))
+        .unwrap();
+        let auth = CredentialConfig::Token {
+            token: String::from("t0ken"),
+        };
+        let service = Service::new(create_router(&config, &auth));
+        let url = "http://127.0.0.1:8080/actuator/loggers";
+
+        let res = TestClient::get(url).send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+
+        let res = TestClient::put(url)
+            .bearer_auth("t0ken")
+            .json(&json!({ "directives": "info,sqlx=[", "duration": 1 }))
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+        let mut res = TestClient::put(url)
+            .bearer_auth("t0ken")
+            .json(&json!({ "directives": "debug", "duration": 3600 }))
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+        let body: Value = res.take_json().await.unwrap();
+        assert!(body["msg"].as_str().unwrap().contains("1 ~ 60"));
+
+        let mut res = TestClient::put(url)
+            .bearer_auth("t0ken")
+            .json(&json!({ "directives": "info,daoyi_cloud_common=debug", "duration": 1 }))
+            .send(&service)
+            .await;
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["data"]["current"], "info,daoyi_cloud_common=debug");
+        assert_eq!(body["data"]["default"], "info");
+        assert_eq!(debug_events(), 1);
+
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+        let mut res = TestClient::get(url)
+            .bearer_auth("t0ken")
+            .send(&service)
+            .await;
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["data"]["current"], "info");
+        assert!(body["data"].get("revert_in").is_none());
+        assert_eq!(debug_events(), 0);
+
+        // 显式 null 或 0 永久生效
+        for duration in [Value::Null, json!(0)] {
+            let mut res = TestClient::put(url)
+                .bearer_auth("t0ken")
+                .json(&json!({ "directives": "debug", "duration": duration }))
+                .send(&service)
+                .await;
+            let body: Value = res.take_json().await.unwrap();
+            assert_eq!(body["data"]["current"], "debug");
+            assert!(body["data"].get("revert_in").is_none());
+        }
+        assert_eq!(debug_events(), 1);
+    }
+}
@@ -1,19 +1,318 @@
-use tracing_subscriber::EnvFilter;
+pub mod admin;
+
+use crate::conf::{LoggingConfig, TelemetryConfig};
+use crate::conf::logging::{FileLogConfig, LogFormat, LogRotation};
+use anyhow::Context;
+use rolling_file::{RollingConditionBasic, RollingFileAppender};
+use salvo::oapi::ToSchema;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::Subscriber;
+use tracing::subscriber::DefaultGuard;
+use tracing_appender::non_blocking::{NonBlockingBuilder, WorkerGuard};
 use tracing_subscriber::fmt::time::ChronoLocal;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry, reload};
+
+type BoxedLayer<S> = Box<dyn Layer<S> + Send + Sync>;
+type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// 启动日志缓存上限，超出部分丢弃
+const BOOTSTRAP_BUFFER_LIMIT: usize = 1024 * 1024;
+
+static BOOTSTRAP_BUFFER: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+static WORKER_GUARDS: Mutex<Vec<WorkerGuard>> = Mutex::new(Vec::new());
+static LEVELS: OnceLock<Mutex<LevelState>> = OnceLock::new();
+
+struct LevelState {
+    handle: FilterHandle,
+    default: String,
+    current: String,
+    revert_at: Option<Instant>,
+    /// 每次调整递增，用于使过期的自动恢复任务失效
+    generation: u64,
+}
+
+/// 日志过滤指令状态
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LogLevels {
+    /// 启动时的过滤指令
+    pub default: String,
+    /// 当前生效的过滤指令
+    pub current: String,
+    /// 距自动恢复为默认指令的剩余秒数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_in: Option<u64>,
+}
+
+/// 加载配置前使用的临时日志：输出到控制台并缓存，`init` 时写入配置的日志文件。
+/// 返回的 guard 释放后失效，只对当前线程生效。
+pub fn bootstrap() -> DefaultGuard {
+    let layers = vec![
+        fmt_layer(LogFormat::Text, BoxMakeWriter::new(std::io::stdout), true),
+        fmt_layer(
+            LogFormat::Text,
+            BoxMakeWriter::new(|| BootstrapWriter),
+            false,
+        ),
+    ];
+    let subscriber = tracing_subscriber::registry()
+        .with(layers)
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")));
+    tracing::subscriber::set_default(subscriber)
+}
+
+/// 按配置初始化日志，`telemetry` 启用时同时挂载 OpenTelemetry 层
+pub fn init(app_name: &str, config: &LoggingConfig, telemetry: &TelemetryConfig) {
+    let bootstrap =
+        std::mem::take(&mut *BOOTSTRAP_BUFFER.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut errors = Vec::new();
+    let mut layers: Vec<BoxedLayer<_>> = Vec::new();
+    if config.console() {
+        let writer = make_writer(config, std::io::stdout());
+        layers.push(fmt_layer(config.format(), writer, true));
+    }
+    if let Some(file) = config.file() {
+        match open_file(file, &bootstrap) {
+            Ok(appender) => {
+                let writer = make_writer(config, appender);
+                layers.push(fmt_layer(config.format(), writer, false));
+            }
+            Err(e) => errors.push(format!("Failed to open log file {}: {}", file.path(), e)),
+        }
+    }
+    // RUST_LOG 优先于配置
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(config.directives()))
+        .unwrap_or_else(|e| {
+            errors.push(format!(
+                "Invalid log directives `{}`: {}",
+                config.directives(),
+                e
+            ));
+            EnvFilter::new("info")
+        });
+    // 过滤层可在运行时替换；OpenTelemetry 层需要 downcast，不能放在 reload 层内
+    let (filter, handle) = reload::Layer::new(filter);
+    install_levels(handle);
+    let registry = tracing_subscriber::registry().with(filter).with(layers);
+
+    #[cfg(feature = "otel")]
+    {
+        let otel = match crate::telemetry::layer(telemetry, app_name) {
+            Ok(layer) => layer,
+            Err(e) => {
+                errors.push(format!("Failed to initialize OpenTelemetry: {e:#}"));
+                None
+            }
+        };
+        registry.with(otel).init();
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        registry.init();
+        if telemetry.enabled() {
+            errors.push(format!(
+                "telemetry is enabled for `{app_name}` but the `otel` feature is not compiled in"
+            ));
+        }
+    }
+    // 日志系统初始化之前的错误延后输出
+    for error in errors {
+        tracing::error!("{}", error);
+    }
+    crate::shutdown::register("logger", || async {
+        // 释放后台写线程的 guard 会等待剩余日志写完
+        drop(std::mem::take(
+            &mut *WORKER_GUARDS.lock().unwrap_or_else(|e| e.into_inner()),
+        ));
+        std::io::stdout().flush()?;
+        Ok(())
+    });
+}
+
+/// 当前日志过滤指令，`init` 之前返回 `None`
+pub fn levels() -> Option<LogLevels> {
+    LEVELS.get().map(|state| snapshot(&lock(state)))
+}
+
+/// 替换日志过滤指令，`revert_after` 到期后自动恢复为启动时的指令
+pub fn set_levels(directives: &str, revert_after: Option<Duration>) -> anyhow::Result<LogLevels> {
+    let filter = EnvFilter::try_new(directives)
+        .with_context(|| format!("Invalid log directives `{directives}`"))?;
+    let state = LEVELS.get().with_context(|| "Logger is not initialized")?;
+    let mut state = lock(state);
+    state.handle.reload(filter)?;
+    state.current = directives.to_string();
+    state.generation += 1;
+    state.revert_at = revert_after.map(|after| Instant::now() + after);
+    tracing::warn!("Log directives changed to `{}`", directives);
+    if let Some(after) = revert_after {
+        let generation = state.generation;
+        tokio::spawn(async move {
+            tokio::time::sleep(after).await;
+            if let Err(e) = revert(generation) {
+                tracing::error!("Failed to revert log directives: {:#}", e);
+            }
+        });
+    }
+    Ok(snapshot(&state))
+}
+
+/// 恢复为启动时的日志过滤指令
+pub fn reset_levels() -> anyhow::Result<LogLevels> {
+    let state = LEVELS.get().with_context(|| "Logger is not initialized")?;
+    let mut state = lock(state);
+    let default = state.default.clone();
+    state.handle.reload(EnvFilter::try_new(&default)?)?;
+    state.current = default;
+    state.generation += 1;
+    state.revert_at = None;
+    tracing::warn!("Log directives reset to `{}`", state.current);
+    Ok(snapshot(&state))
+}
+
+/// 自动恢复，期间有过新的调整则跳过
+fn revert(generation: u64) -> anyhow::Result<()> {
+    let current = LEVELS.get().map(|state| lock(state).generation);
+    if current == Some(generation) {
+        reset_levels()?;
+    }
+    Ok(())
+}
+
+fn install_levels(handle: FilterHandle) {
+    let default = handle
+        .with_current(|filter| filter.to_string())
+        .unwrap_or_default();
+    let state = LevelState {
+        handle,
+        current: default.clone(),
+        default,
+        revert_at: None,
+        generation: 0,
+    };
+    if LEVELS.set(Mutex::new(state)).is_err() {
+        tracing::warn!("Log level handle is already installed");
+    }
+}
+
+fn lock(state: &Mutex<LevelState>) -> std::sync::MutexGuard<'_, LevelState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn snapshot(state: &LevelState) -> LogLevels {
+    LogLevels {
+        default: state.default.clone(),
+        current: state.current.clone(),
+        revert_in: state
+            .revert_at
+            .map(|at| at.saturating_duration_since(Instant::now()).as_secs()),
+    }
+}
+
+fn fmt_layer<S>(format: LogFormat, writer: BoxMakeWriter, ansi: bool) -> BoxedLayer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_timer(ChronoLocal::new(String::from("%Y-%m-%d %H:%M:%S%.6f")))
+        .with_file(true)
+        .with_line_number(true)
+        .with_thread_ids(true)
+        .with_thread_names(true)
+        .with_ansi(ansi)
+        .with_writer(writer);
+    match format {
+        LogFormat::Text => layer.with_target(false).boxed(),
+        LogFormat::Json => layer
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    }
+}
+
+fn make_writer<W: Write + Send + Sync + 'static>(
+    config: &LoggingConfig,
+    writer: W,
+) -> BoxMakeWriter {
+    if config.non_blocking() {
+        let (writer, guard) = NonBlockingBuilder::default().lossy(false).finish(writer);
+        WORKER_GUARDS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(guard);
+        BoxMakeWriter::new(writer)
+    } else {
+        BoxMakeWriter::new(Mutex::new(writer))
+    }
+}
+
+/// 打开滚动日志文件，并写入启动阶段缓存的日志
+fn open_file(
+    config: &FileLogConfig,
+    bootstrap: &[u8],
+) -> std::io::Result<RollingFileAppender<RollingConditionBasic>> {
+    let path = Path::new(config.path());
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut condition = match config.rotation() {
+        LogRotation::Daily => RollingConditionBasic::new().daily(),
+        LogRotation::Hourly => RollingConditionBasic::new().hourly(),
+        LogRotation::Never => RollingConditionBasic::new(),
+    };
+    if let Some(max_size) = config.max_size() {
+        condition = condition.max_size(max_size);
+    }
+    let mut appender = RollingFileAppender::new(path, condition, config.max_files())?;
+    appender.write_all(bootstrap)?;
+    appender.flush()?;
+    Ok(appender)
+}
+
+struct BootstrapWriter;
+
+impl Write for BootstrapWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut buffer = BOOTSTRAP_BUFFER.lock().unwrap_or_else(|e| e.into_inner());
+        if buffer.len() + buf.len() <= BOOTSTRAP_BUFFER_LIMIT {
+            buffer.extend_from_slice(buf);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-pub fn init() {
-    tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
-        .with(
-            tracing_subscriber::fmt::layer()
-                .with_timer(ChronoLocal::new(String::from("%Y-%m-%d %H:%M:%S%.6f")))
-                .with_file(true)
-                .with_line_number(true)
-                .with_thread_ids(true)
-                .with_thread_names(true)
-                .with_target(false),
-        )
-        .init();
+    #[test]
+    fn test_open_file_with_bootstrap() {
+        let dir = std::env::temp_dir().join(format!("daoyi-logger-{}", std::process::id()));
+        let path = dir.join("nested/app.log");
+        let config: FileLogConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "path": path.to_str().unwrap(),
+            "max_size": "1KiB",
+        }))
+        .unwrap();
+        let mut appender = open_file(&config, b"bootstrap line\n").unwrap();
+        appender.write_all(b"configured line\n").unwrap();
+        appender.flush().unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "bootstrap line\nconfigured line\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
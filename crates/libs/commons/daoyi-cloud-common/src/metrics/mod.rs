@@ -0,0 +1,373 @@
+//! Prometheus 指标，业务模块可直接使用重新导出的宏注册自己的指标：
+//!
+//! ```ignore
+//! daoyi_cloud_common::metrics::counter!("auth_login_total", "result" => "success").increment(1);
+//! ```
+
+use crate::conf::MetricsConfig;
+use crate::db;
+use ::metrics::{Unit, describe_counter, describe_gauge, describe_histogram};
+use anyhow::Context;
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use salvo::prelude::*;
+use sea_orm::DatabaseConnection;
+#[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+use sea_orm::DbBackend;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+pub use ::metrics::{counter, gauge, histogram};
+
+const HTTP_REQUESTS: &str = "http_server_requests_total";
+const HTTP_DURATION: &str = "http_server_request_duration_seconds";
+const HTTP_IN_FLIGHT: &str = "http_server_requests_in_flight";
+const DB_QUERY_DURATION: &str = "db_query_duration_seconds";
+const DB_POOL_ACQUIRE: &str = "db_pool_acquire_duration_seconds";
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+static START_TIME: OnceLock<f64> = OnceLock::new();
+
+/// 安装全局 Prometheus 记录器，未初始化时所有指标操作均为空操作
+pub fn init(config: &MetricsConfig) -> anyhow::Result<()> {
+    let buckets = config.buckets();
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Full(HTTP_DURATION.to_string()), buckets)?
+        .set_buckets_for_metric(Matcher::Full(DB_QUERY_DURATION.to_string()), buckets)?
+        .set_buckets_for_metric(Matcher::Full(DB_POOL_ACQUIRE.to_string()), buckets)?
+        .install_recorder()
+        .with_context(|| "Failed to install metrics recorder")?;
+    describe();
+    HANDLE
+        .set(handle.clone())
+        .map_err(|_| anyhow::anyhow!("Metrics already initialized"))?;
+    START_TIME.get_or_init(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or_default()
+    });
+    // 定期清理直方图等指标的过期数据
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            interval.tick().await;
+            handle.run_upkeep();
+        }
+    });
+    // 单独探测连接池，避免连接池耗尽时阻塞指标清理
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            interval.tick().await;
+            if let Some(conn) = db::try_get() {
+                probe_db_pool(conn).await;
+            }
+        }
+    });
+    Ok(())
+}
+
+fn describe() {
+    describe_counter!(HTTP_REQUESTS, "HTTP 请求总数");
+    describe_histogram!(HTTP_DURATION, Unit::Seconds, "HTTP 请求耗时");
+    describe_gauge!(HTTP_IN_FLIGHT, "正在处理的 HTTP 请求数");
+    describe_histogram!(DB_QUERY_DURATION, Unit::Seconds, "数据库查询耗时");
+    describe_gauge!("db_pool_connections", "连接池当前连接数，按 state 区分空闲与使用中");
+    describe_gauge!("db_pool_max_connections", "连接池最大连接数");
+    describe_histogram!(DB_POOL_ACQUIRE, Unit::Seconds, "探测获取连接池连接的等待耗时");
+    describe_gauge!("process_cpu_seconds_total", Unit::Seconds, "进程累计 CPU 时间");
+    describe_gauge!("process_resident_memory_bytes", Unit::Bytes, "进程常驻内存");
+    describe_gauge!("process_open_fds", "进程打开的文件描述符数");
+    describe_gauge!("process_threads", "进程线程数");
+    describe_gauge!("process_start_time_seconds", Unit::Seconds, "进程启动时间（Unix 时间戳）");
+    describe_gauge!("tokio_workers", "Tokio 工作线程数");
+    describe_gauge!("tokio_alive_tasks", "Tokio 存活任务数");
+    describe_gauge!("tokio_global_queue_depth", "Tokio 全局队列中等待调度的任务数");
+}
+
+/// 渲染 Prometheus 文本格式，抓取时同步采集进程、运行时及连接池指标
+pub fn render() -> Option<String> {
+    let handle = HANDLE.get()?;
+    collect_process();
+    collect_runtime();
+    if let Some(conn) = db::try_get() {
+        collect_db_pool(conn);
+    }
+    Some(handle.render())
+}
+
+fn collect_process() {
+    if let Some(start_time) = START_TIME.get() {
+        gauge!("process_start_time_seconds").set(*start_time);
+    }
+    #[cfg(unix)]
+    {
+        let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+        // SAFETY: usage 仅在 getrusage 成功返回后读取
+        if unsafe { libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) } == 0 {
+            let usage = unsafe { usage.assume_init() };
+            let seconds = |tv: libc::timeval| tv.tv_sec as f64 + tv.tv_usec as f64 / 1e6;
+            gauge!("process_cpu_seconds_total")
+                .set(seconds(usage.ru_utime) + seconds(usage.ru_stime));
+        }
+    }
+    #[cfg(target_os = "linux")]
+    {
+        if let Ok(statm) = std::fs::read_to_string("/proc/self/statm")
+            && let Some(pages) = statm.split_whitespace().nth(1)
+            && let Ok(pages) = pages.parse::<f64>()
+        {
+            // SAFETY: sysconf 无副作用
+            let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as f64;
+            gauge!("process_resident_memory_bytes").set(pages * page_size);
+        }
+        if let Ok(fds) = std::fs::read_dir("/proc/self/fd") {
+            gauge!("process_open_fds").set(fds.count() as f64);
+        }
+        if let Ok(tasks) = std::fs::read_dir("/proc/self/task") {
+            gauge!("process_threads").set(tasks.count() as f64);
+        }
+    }
+}
+
+fn collect_runtime() {
+    let runtime = tokio::runtime::Handle::current().metrics();
+    gauge!("tokio_workers").set(runtime.num_workers() as f64);
+    gauge!("tokio_alive_tasks").set(runtime.num_alive_tasks() as f64);
+    gauge!("tokio_global_queue_depth").set(runtime.global_queue_depth() as f64);
+}
+
+#[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+fn collect_db_pool(conn: &DatabaseConnection) {
+    let (size, idle, max) = match conn.get_database_backend() {
+        #[cfg(feature = "mysql")]
+        DbBackend::MySql => pool_stats(conn.get_mysql_connection_pool()),
+        #[cfg(feature = "postgres")]
+        DbBackend::Postgres => pool_stats(conn.get_postgres_connection_pool()),
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => pool_stats(conn.get_sqlite_connection_pool()),
+        #[allow(unreachable_patterns)]
+        _ => return,
+    };
+    gauge!("db_pool_connections", "state" => "idle").set(idle as f64);
+    gauge!("db_pool_connections", "state" => "in_use").set(size.saturating_sub(idle) as f64);
+    gauge!("db_pool_max_connections").set(max as f64);
+}
+
+/// 未启用任何数据库后端时 sea_orm 不导出 sqlx，无连接池指标可采集
+#[cfg(not(any(feature = "mysql", feature = "postgres", feature = "sqlite")))]
+fn collect_db_pool(_conn: &DatabaseConnection) {}
+
+#[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+fn pool_stats<DB: sea_orm::sqlx::Database>(pool: &sea_orm::sqlx::Pool<DB>) -> (u32, u32, u32) {
+    let idle = u32::try_from(pool.num_idle()).unwrap_or(u32::MAX);
+    (pool.size(), idle, pool.options().get_max_connections())
+}
+
+/// 探测一次获取连接的等待耗时。sqlx 未公开排队等待数，连接池耗尽时探测与业务请求一同排队，
+/// 耗时即反映获取连接的等待时间，超时按 error 记录
+#[cfg(any(feature = "mysql", feature = "postgres", feature = "sqlite"))]
+async fn probe_db_pool(conn: &DatabaseConnection) {
+    let start = Instant::now();
+    let acquired = match conn.get_database_backend() {
+        #[cfg(feature = "mysql")]
+        DbBackend::MySql => conn.get_mysql_connection_pool().acquire().await.is_ok(),
+        #[cfg(feature = "postgres")]
+        DbBackend::Postgres => conn.get_postgres_connection_pool().acquire().await.is_ok(),
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => conn.get_sqlite_connection_pool().acquire().await.is_ok(),
+        #[allow(unreachable_patterns)]
+        _ => return,
+    };
+    let outcome = if acquired { "success" } else { "error" };
+    histogram!(DB_POOL_ACQUIRE, "outcome" => outcome).record(start.elapsed().as_secs_f64());
+}
+
+#[cfg(not(any(feature = "mysql", feature = "postgres", feature = "sqlite")))]
+async fn probe_db_pool(_conn: &DatabaseConnection) {}
+
+/// 记录一次数据库查询，按 SQL 操作类型与结果区分
+pub fn record_db_query(info: &sea_orm::metric::Info<'_>) {
+    let operation = info
+        .statement
+        .sql
+        .split_whitespace()
+        .next()
+        .map(str::to_ascii_uppercase)
+        .unwrap_or_default();
+    let outcome = if info.failed { "error" } else { "success" };
+    histogram!(DB_QUERY_DURATION, "operation" => operation, "outcome" => outcome)
+        .record(info.elapsed.as_secs_f64());
+}
+
+/// 请求指标中间件，需挂载在 Service 上以覆盖全部请求
+pub struct RequestMetrics;
+
+#[handler]
+impl RequestMetrics {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let start = Instant::now();
+        let in_flight = InFlight::start();
+        ctrl.call_next(req, depot, res).await;
+        drop(in_flight);
+
+        // 按路由模板而非实际路径统计，避免路径参数导致标签爆炸；
+        // 未匹配路由（404、405 或被前置中间件改写状态码）统一归为 unmatched
+        let route = match req.matched_path() {
+            "" => String::from("unmatched"),
+            path => format!("/{path}"),
+        };
+        let labels = [
+            ("method", req.method().to_string()),
+            ("route", route),
+            (
+                "status",
+                res.status_code.unwrap_or(StatusCode::OK).as_u16().to_string(),
+            ),
+        ];
+        counter!(HTTP_REQUESTS, &labels).increment(1);
+        histogram!(HTTP_DURATION, &labels).record(start.elapsed().as_secs_f64());
+    }
+}
+
+/// 在途请求计数，请求结束或被取消（如客户端断开）时随 Drop 递减
+struct InFlight(::metrics::Gauge);
+
+impl InFlight {
+    fn start() -> Self {
+        let gauge = gauge!(HTTP_IN_FLIGHT);
+        gauge.increment(1);
+        Self(gauge)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.decrement(1);
+    }
+}
+
+/// 指标抓取路由
+pub fn create_router(config: &MetricsConfig) -> Router {
+    Router::with_path(config.path().trim_start_matches('/')).get(scrape)
+}
+
+#[handler]
+async fn scrape(res: &mut Response) {
+    match render() {
+        Some(body) => {
+            res.add_header("content-type", "text/plain; version=0.0.4", true)
+                .ok();
+            res.render(body);
+        }
+        None => {
+            res.status_code(StatusCode::NOT_FOUND);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+
+    static INIT: std::sync::Once = std::sync::Once::new();
+
+    fn setup(config: &MetricsConfig) {
+        INIT.call_once(|| init(config).unwrap());
+    }
+
+    #[handler]
+    async fn user() -> &'static str {
+        "user"
+    }
+
+    #[handler]
+    async fn hang() {
+        std::future::pending::<()>().await
+    }
+
+    #[tokio::test]
+    async fn test_request_metrics() {
+        let config = MetricsConfig::default();
+        setup(&config);
+        let router = Router::new()
+            .push(Router::with_path("users/{id}").get(user))
+            .push(Router::with_path("hang").get(hang))
+            .push(create_router(&config));
+        let service = Service::new(router).hoop(RequestMetrics);
+
+        for id in [1, 2] {
+            TestClient::get(format!("http://127.0.0.1:8080/users/{id}"))
+                .send(&service)
+                .await;
+        }
+        TestClient::get("http://127.0.0.1:8080/missing/1")
+            .send(&service)
+            .await;
+        TestClient::post("http://127.0.0.1:8080/users/1")
+            .send(&service)
+            .await;
+        // 模拟客户端断开：请求处理中途被取消
+        let pending = TestClient::get("http://127.0.0.1:8080/hang").send(&service);
+        let cancelled = tokio::time::timeout(Duration::from_millis(10), pending).await;
+        assert!(cancelled.is_err());
+        counter!("auth_login_total", "result" => "success").increment(1);
+
+        let body = TestClient::get("http://127.0.0.1:8080/metrics")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert!(body.contains(
+            r#"http_server_requests_total{method="GET",route="/users/{id}",status="200"} 2"#
+        ));
+        assert!(body.contains(
+            r#"http_server_requests_total{method="GET",route="unmatched",status="404"} 1"#
+        ));
+        assert!(body.contains(
+            r#"http_server_requests_total{method="POST",route="unmatched",status="405"} 1"#
+        ));
+        assert!(body.contains(
+            r#"http_server_request_duration_seconds_bucket{method="GET",route="/users/{id}",status="200",le="+Inf"} 2"#
+        ));
+        assert!(body.contains(r#"auth_login_total{result="success"} 1"#));
+        // 仅剩抓取请求自身，被取消的请求已递减
+        assert!(body.contains("http_server_requests_in_flight 1"));
+        assert!(body.contains("tokio_workers"));
+        assert!(body.contains("process_start_time_seconds"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_probe_db_pool() {
+        setup(&MetricsConfig::default());
+        let mut options = sea_orm::ConnectOptions::new("sqlite::memory:");
+        options.max_connections(1);
+        let conn = sea_orm::Database::connect(options).await.unwrap();
+
+        // 占满连接池，探测需排队至连接归还
+        let held = conn.get_sqlite_connection_pool().acquire().await.unwrap();
+        let probe = tokio::spawn(async move { probe_db_pool(&conn).await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(held);
+        probe.await.unwrap();
+
+        let body = render().unwrap();
+        let sum = body
+            .lines()
+            .find_map(|line| {
+                line.strip_prefix(r#"db_pool_acquire_duration_seconds_sum{outcome="success"} "#)
+            })
+            .and_then(|sum| sum.parse::<f64>().ok())
+            .unwrap();
+        assert!(sum >= 0.05);
+    }
+}
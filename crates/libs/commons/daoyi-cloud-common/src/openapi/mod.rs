@@ -1 +1,191 @@
+use crate::auth::credential::CredentialGuard;
+use crate::conf::OpenApiConfig;
+use crate::error::{self, ErrorCodeGroup};
+use crate::response::complete_error_responses;
+use salvo::oapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityScheme};
+use salvo::oapi::{BasicType, Contact, Info, KnownFormat, Object, OpenApi, SchemaFormat, Server};
+use salvo::prelude::*;
+use salvo_oapi::scalar::Scalar;
+use salvo_oapi::swagger_ui::SwaggerUi;
 
+mod validate_schema;
+
+pub use validate_schema::{FieldName, FieldRule, ValidateSchema, custom_rule, register_custom_rule};
+
+/// 按配置生成 OpenAPI 文档（JWT Bearer + 租户 ID 安全方案），附带错误码目录
+pub fn create_doc(config: &OpenApiConfig, router: &Router) -> OpenApi {
+    let mut info = Info::new(config.title(), config.version());
+    if let Some(description) = config.description() {
+        info = info.description(description);
+    }
+    if let Some(contact) = config.contact() {
+        let mut c = Contact::new();
+        if let Some(name) = contact.name() {
+            c = c.name(name);
+        }
+        if let Some(email) = contact.email() {
+            c = c.email(email);
+        }
+        if let Some(url) = contact.url() {
+            c = c.url(url);
+        }
+        info = info.contact(c);
+    }
+    let servers = config.servers().iter().map(|server| {
+        let s = Server::new(server.url());
+        match server.description() {
+            Some(description) => s.description(description),
+            None => s,
+        }
+    });
+    let catalog = error::catalog();
+    let mut doc = OpenApi::with_info(info)
+        .servers(servers)
+        .add_schema("ErrorCode", error_code_schema(&catalog))
+        .add_extension(
+            "x-error-codes",
+            serde_json::to_value(&catalog).unwrap_or_default(),
+        )
+        .add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer).bearer_format("JWT")),
+        )
+        .add_security_scheme(
+            "tenant_id",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                "tenant-id",
+                "租户ID",
+            ))),
+        )
+        .merge_router(router);
+    complete_error_responses(&mut doc);
+    doc
+}
+
+/// 错误码枚举，`x-enum-varnames` / `x-enum-descriptions` 供客户端生成代码
+fn error_code_schema(catalog: &[ErrorCodeGroup]) -> Object {
+    let codes: Vec<_> = catalog.iter().flat_map(|group| group.codes).collect();
+    let mut description =
+        String::from("错误码，0 表示成功\n\n| 错误码 | 名称 | 提示信息 |\n| --- | --- | --- |\n");
+    for code in &codes {
+        description.push_str(&format!(
+            "| {} | {} | {} |\n",
+            code.code, code.name, code.message
+        ));
+    }
+    Object::new()
+        .schema_type(BasicType::Integer)
+        .format(SchemaFormat::KnownFormat(KnownFormat::Int32))
+        .description(description)
+        .enum_values(codes.iter().map(|code| code.code))
+        .add_extension(
+            "x-enum-varnames",
+            codes.iter().map(|code| code.name).collect(),
+        )
+        .add_extension(
+            "x-enum-descriptions",
+            codes.iter().map(|code| code.message).collect(),
+        )
+}
+
+#[handler]
+async fn error_codes(res: &mut Response) {
+    res.render(Json(error::catalog()));
+}
+
+/// 文档路由：OpenAPI JSON、错误码目录及已启用的 Swagger UI / Scalar，配置了访问保护时统一加上校验
+pub fn create_router(config: &OpenApiConfig, router: &Router) -> Router {
+    let json_path = config.json_path();
+    let mut doc_router = Router::new()
+        .push(create_doc(config, router).into_router(json_path))
+        .push(Router::with_path(config.error_codes_path()).get(error_codes));
+    if let Some(path) = config.swagger_ui() {
+        doc_router = doc_router.push(
+            SwaggerUi::new(format!("{path}/{{_:.*}}"))
+                .url(json_path.to_owned())
+                .into_router(path),
+        );
+    }
+    if let Some(path) = config.scalar() {
+        doc_router = doc_router.push(Scalar::new(json_path.to_owned()).into_router(path));
+    }
+    match config.auth() {
+        Some(auth) => doc_router.hoop(CredentialGuard::new(auth.clone(), "api-docs")),
+        None => doc_router,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+
+    fn service(auth: serde_json::Value) -> Service {
+        let config: OpenApiConfig = serde_json::from_value(serde_json::json!({
+            "title": "Test API",
+            "scalar": { "enabled": false },
+            "auth": auth,
+        }))
+        .unwrap();
+        let router = Router::new();
+        Service::new(Router::new().push(create_router(&config, &router)))
+    }
+
+    #[tokio::test]
+    async fn test_basic_auth() {
+        let service = service(serde_json::json!({
+            "type": "basic", "username": "admin", "password": "secret",
+        }));
+        let url = "http://127.0.0.1:8080/api-docs/openapi.json";
+        let res = TestClient::get(url).send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        assert!(res.headers().contains_key("www-authenticate"));
+
+        let mut res = TestClient::get(url)
+            .basic_auth("admin", Some("secret"))
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        let doc: serde_json::Value = res.take_json().await.unwrap();
+        assert_eq!(doc["info"]["title"], "Test API");
+        assert_eq!(doc["info"]["version"], env!("CARGO_PKG_VERSION"));
+        let schema = &doc["components"]["schemas"]["ErrorCode"];
+        assert_eq!(schema["x-enum-varnames"][0], "BIZ");
+        assert_eq!(doc["x-error-codes"][0]["module"], "common");
+
+        let mut res = TestClient::get("http://127.0.0.1:8080/api-docs/error-codes.json")
+            .basic_auth("admin", Some("secret"))
+            .send(&service)
+            .await;
+        let catalog: serde_json::Value = res.take_json().await.unwrap();
+        let codes = catalog[0]["codes"].as_array().unwrap();
+        assert!(
+            codes
+                .iter()
+                .any(|code| code["code"] == 1_010 && code["name"] == "TOO_MANY_REQUESTS")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_auth() {
+        let service = service(serde_json::json!({ "type": "token", "token": "t0ken" }));
+        let url = "http://127.0.0.1:8080/api-docs/openapi.json";
+        let res = TestClient::get(url).bearer_auth("wrong").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        let res = TestClient::get(url).bearer_auth("t0ken").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        let res = TestClient::get(format!("{url}?token=t0ken")).send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_ui() {
+        let service = service(serde_json::Value::Null);
+        let res = TestClient::get("http://127.0.0.1:8080/scalar").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        let res = TestClient::get("http://127.0.0.1:8080/swagger-ui/index.html")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+    }
+}
@@ -0,0 +1,275 @@
+//! 将 `validator` 校验规则写入 OpenAPI schema：`#[derive(ValidateSchema)]` 从 `#[validate(...)]` 生成
+//! 各字段的 [`FieldRule`]，登记请求参数时由 [`ValidateSchema::register`] 补充到 `ToSchema` 的输出中。
+
+use crate::constants::global_values::{
+    CREDIT_CODE_REGEX, ID_CARD_REGEX, MOBILE_PHONE_REGEX, PLATE_NUMBER_REGEX, POSTAL_CODE_REGEX,
+};
+use crate::extract::validations::MAX_PAGE_SIZE;
+use salvo::oapi::{Components, KnownFormat, Number, RefOr, Schema, SchemaFormat, ToSchema};
+use std::sync::RwLock;
+
+pub use daoyi_cloud_macros::ValidateSchema;
+
+static CUSTOM_RULES: RwLock<Vec<(&'static str, FieldRule)>> = RwLock::new(Vec::new());
+
+/// 带校验约束的 schema，由 `#[derive(ValidateSchema)]` 实现，需同时派生 `ToSchema`
+pub trait ValidateSchema: ToSchema {
+    /// 各字段的校验约束
+    fn field_rules() -> Vec<FieldRule>;
+
+    /// 登记 `#[validate(nested)]` 字段类型的约束
+    fn register_nested(_components: &mut Components) {}
+
+    /// 各字段序列化后的名称，校验错误据此使用 JSON 字段名
+    fn field_names() -> Vec<FieldName> {
+        Vec::new()
+    }
+
+    /// 生成 schema 并写入校验约束，替代 `ToSchema::to_schema`
+    fn register(components: &mut Components) -> RefOr<Schema> {
+        let mut schema = Self::to_schema(components);
+        Self::register_nested(components);
+        let rules = Self::field_rules();
+        if let RefOr::Ref(reference) = &schema {
+            let name = reference
+                .ref_location
+                .trim_start_matches("#/components/schemas/");
+            if let Some(target) = components.schemas.0.get_mut(name) {
+                apply_rules(target, &rules);
+            }
+        } else {
+            apply_rules(&mut schema, &rules);
+        }
+        schema
+    }
+}
+
+/// 单个字段的校验约束，字段名为序列化后的名称
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldRule {
+    pub field: &'static str,
+    pub required: bool,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+    pub minimum: Option<f64>,
+    pub maximum: Option<f64>,
+    pub exclusive_minimum: Option<f64>,
+    pub exclusive_maximum: Option<f64>,
+    pub pattern: Option<String>,
+    /// `email`、`uri` 等格式
+    pub format: Option<&'static str>,
+    /// `custom(function = ...)` 的函数名，约束取自 [`register_custom_rule`]
+    pub custom: Option<&'static str>,
+}
+
+/// 字段的 Rust 名称与序列化名称
+#[derive(Debug, Clone, Copy)]
+pub struct FieldName {
+    /// Rust 字段名，即 `validator` 报告错误使用的名称
+    pub field: &'static str,
+    /// 序列化后的名称，`#[serde(flatten)]` 字段为空
+    pub name: &'static str,
+    /// `#[validate(nested)]` 字段类型的名称映射
+    pub nested: Option<fn() -> Vec<FieldName>>,
+}
+
+/// 登记自定义校验函数对应的 schema 约束（`field` 无需填写），按函数名匹配；
+/// 需在生成文档前调用，同名时后登记的生效
+pub fn register_custom_rule(function: &'static str, rule: FieldRule) {
+    let mut rules = CUSTOM_RULES.write().unwrap_or_else(|e| e.into_inner());
+    rules.retain(|(name, _)| *name != function);
+    rules.push((function, rule));
+}
+
+/// 自定义校验函数对应的约束：已登记的优先，其次为内置校验函数
+pub fn custom_rule(function: &str) -> Option<FieldRule> {
+    let registered = CUSTOM_RULES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(name, _)| *name == function)
+        .map(|(_, rule)| rule.clone());
+    registered.or_else(|| match function {
+        "validate_mobile_phone" => Some(FieldRule {
+            pattern: Some(MOBILE_PHONE_REGEX.as_str().to_string()),
+            ..Default::default()
+        }),
+        "validate_id_card" => Some(FieldRule {
+            pattern: Some(ID_CARD_REGEX.as_str().to_string()),
+            ..Default::default()
+        }),
+        "validate_credit_code" => Some(FieldRule {
+            pattern: Some(CREDIT_CODE_REGEX.as_str().to_string()),
+            ..Default::default()
+        }),
+        "validate_postal_code" => Some(FieldRule {
+            pattern: Some(POSTAL_CODE_REGEX.as_str().to_string()),
+            ..Default::default()
+        }),
+        "validate_plate_number" => Some(FieldRule {
+            pattern: Some(PLATE_NUMBER_REGEX.as_str().to_string()),
+            ..Default::default()
+        }),
+        "validate_email" => Some(FieldRule {
+            format: Some("email"),
+            ..Default::default()
+        }),
+        "validate_http_url" => Some(FieldRule {
+            format: Some("uri"),
+            ..Default::default()
+        }),
+        "validate_page_size" => Some(FieldRule {
+            minimum: Some(1.0),
+            maximum: Some(MAX_PAGE_SIZE as f64),
+            ..Default::default()
+        }),
+        _ => None,
+    })
+}
+
+/// 写入对象 schema 的各属性，`#[serde(flatten)]` 生成的 `allOf` 逐项处理
+fn apply_rules(schema: &mut RefOr<Schema>, rules: &[FieldRule]) {
+    match schema {
+        RefOr::Type(Schema::Object(object)) => {
+            for rule in rules {
+                let Some(property) = object.properties.get_mut(rule.field) else {
+                    continue;
+                };
+                if rule.required {
+                    object.required.insert(rule.field.to_string());
+                }
+                apply_rule(property, rule);
+                if let Some(custom) = rule.custom.and_then(custom_rule) {
+                    apply_rule(property, &custom);
+                }
+            }
+        }
+        RefOr::Type(Schema::AllOf(all_of)) => {
+            for item in &mut all_of.items {
+                apply_rules(item, rules);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply_rule(property: &mut RefOr<Schema>, rule: &FieldRule) {
+    match property {
+        RefOr::Type(Schema::Object(object)) => {
+            set(&mut object.min_length, rule.min_length);
+            set(&mut object.max_length, rule.max_length);
+            set(&mut object.minimum, rule.minimum.map(Number::from));
+            set(&mut object.maximum, rule.maximum.map(Number::from));
+            set(
+                &mut object.exclusive_minimum,
+                rule.exclusive_minimum.map(Number::from),
+            );
+            set(
+                &mut object.exclusive_maximum,
+                rule.exclusive_maximum.map(Number::from),
+            );
+            set(&mut object.pattern, rule.pattern.clone());
+            set(
+                &mut object.format,
+                rule.format.map(|format| match format {
+                    "email" => SchemaFormat::KnownFormat(KnownFormat::Email),
+                    format => SchemaFormat::Custom(format.to_string()),
+                }),
+            );
+        }
+        RefOr::Type(Schema::Array(array)) => {
+            set(&mut array.min_items, rule.min_items);
+            set(&mut array.max_items, rule.max_items);
+        }
+        // Option<T> 可能生成 oneOf [T, null]
+        RefOr::Type(Schema::OneOf(one_of)) => {
+            for item in &mut one_of.items {
+                apply_rule(item, rule);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn set<T>(target: &mut Option<T>, value: Option<T>) {
+    if value.is_some() {
+        *target = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pojo::pagination::PageParam;
+    use serde::Deserialize;
+    use serde_json::Value;
+    use std::sync::LazyLock;
+    use validator::Validate;
+
+    static CODE_REGEX: LazyLock<regex::Regex> =
+        LazyLock::new(|| regex::Regex::new(r"^[A-Z]{3}$").unwrap());
+
+    #[derive(Deserialize, Validate, ToSchema, ValidateSchema)]
+    #[serde(rename_all = "camelCase")]
+    struct Order {
+        #[validate(length(min = 1, max = 16))]
+        nick_name: String,
+        #[validate(email)]
+        contact_email: Option<String>,
+        #[validate(custom(function = "crate::extract::validations::validate_mobile_phone"))]
+        mobile: String,
+        #[validate(regex(path = *CODE_REGEX))]
+        currency: String,
+        #[validate(range(min = 1, exclusive_max = 100))]
+        quantity: i32,
+        #[validate(length(max = 5))]
+        tags: Vec<String>,
+        #[serde(rename = "remark")]
+        #[validate(required, length(max = 200))]
+        note: Option<String>,
+        #[serde(flatten)]
+        #[validate(nested)]
+        pagination: PageParam,
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        let mut components = Components::new();
+        let schema = serde_json::to_value(Order::register(&mut components)).unwrap();
+        let schemas = serde_json::to_value(&components).unwrap()["schemas"].take();
+        let component = |name: &str| {
+            schemas
+                .as_object()
+                .unwrap()
+                .iter()
+                .find(|(key, _)| key.ends_with(name))
+                .unwrap()
+                .1
+        };
+        assert!(schema["$ref"].as_str().unwrap().ends_with(".Order"));
+        // flatten 生成 allOf [PageParam, 其余字段]
+        let properties = &component(".Order")["allOf"][1];
+        let property = |name: &str| &properties["properties"][name];
+        assert_eq!(property("nickName")["minLength"], 1);
+        assert_eq!(property("nickName")["maxLength"], 16);
+        assert_eq!(property("mobile")["pattern"], MOBILE_PHONE_REGEX.as_str());
+        assert_eq!(property("currency")["pattern"], "^[A-Z]{3}$");
+        assert_eq!(property("quantity")["minimum"], 1);
+        assert_eq!(property("quantity")["exclusiveMaximum"], 100);
+        assert_eq!(property("tags")["maxItems"], 5);
+        assert_eq!(property("remark")["maxLength"], 200);
+        assert!(
+            properties["required"]
+                .as_array()
+                .unwrap()
+                .contains(&Value::from("remark"))
+        );
+        assert_eq!(property("contactEmail")["format"], "email");
+
+        let page = &component(".PageParam")["properties"];
+        assert_eq!(page["pageNo"]["minimum"], 1);
+        assert_eq!(page["pageSize"]["maximum"], MAX_PAGE_SIZE);
+    }
+}
@@ -0,0 +1,12 @@
+use crate::openapi::ValidateSchema;
+use salvo::oapi::ToSchema;
+use serde::Deserialize;
+use validator::Validate;
+
+/// 路径中的 ID 参数，如 `/users/{id}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Validate, ToSchema, ValidateSchema)]
+pub struct IdParam {
+    /// ID
+    #[validate(range(min = 1))]
+    pub id: i64,
+}
@@ -1,17 +1,18 @@
 use crate::constants::default_values::default_page_no;
 use crate::constants::default_values::default_page_size;
 use crate::extract::validations::validate_page_size;
+use crate::openapi::ValidateSchema;
 use crate::utils::serde_utils::deserialize_number;
 use salvo::oapi::ToSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 /// 分页查询参数
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Validate, ToSchema, ValidateSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PageParam {
     /// 页码
-    #[validate(range(min = 1, message = "页码最小值为 1"))]
+    #[validate(range(min = 1, message = "{validation.page_no_min}"))]
     #[serde(default = "default_page_no", deserialize_with = "deserialize_number")]
     pub page_no: u64,
     /// 每页条数
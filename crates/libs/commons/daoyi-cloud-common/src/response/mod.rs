@@ -1,22 +1,39 @@
-use salvo::oapi::ToSchema;
+pub mod problem;
+
+use salvo::oapi::{
+    Components, Content, EndpointOutRegister, OpenApi, Operation, RefOr, Schema, ToSchema,
+};
 use salvo::prelude::*;
 use salvo::writing::Json;
 use serde::{Deserialize, Serialize};
 
-use crate::error::ApiError;
+use crate::error::{ApiError, FieldError};
+use problem::{PROBLEM_JSON, ProblemContext, ProblemDetails};
 
+/// 接口返回类型：成功时输出 `ApiResponse<T>`，失败时按 [`write_error_response`] 输出，
+/// 并在 OpenAPI 中自动登记成功响应及可能的错误状态码
+///
+/// ```ignore
+/// #[endpoint]
+/// async fn get_user(path: ValidPath<IdParam>) -> CommonResult<Option<SysUser>> {
+///     Ok(sys_user_service::get_user_by_id(path.id).await?.into())
+/// }
+/// ```
 pub type CommonResult<T> = Result<ApiResponse<T>, ApiError>;
 
 /// 统一 API 响应结构
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ApiResponse<T: Serialize + ToSchema + Send> {
-    /// 状态码，0 表示成功
+    /// 错误码，0 表示成功，取值见错误码目录（`ErrorCode`）
     pub code: i32,
     /// 提示信息
     pub msg: String,
     /// 响应数据
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<T>,
+    /// 请求 ID，错误响应时返回，便于排查
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl<T: Serialize + ToSchema + Send> ApiResponse<T> {
@@ -25,6 +42,7 @@ impl<T: Serialize + ToSchema + Send> ApiResponse<T> {
             code,
             msg: String::from(msg.as_ref()),
             data,
+            request_id: None,
         }
     }
 
@@ -57,9 +75,25 @@ pub fn write_json_response<T: Serialize + ToSchema + Send>(
 /// 将 ApiError 写入 salvo Response
 pub fn write_error_response(res: &mut Response, error: ApiError) {
     let status = error.status_code();
-    let body = ApiResponse::<()>::err_msg(error.to_string());
-    res.status_code(status);
-    res.render(Json(body));
+    let mut body = error.to_api_response();
+    body.request_id = crate::server::access_log::current_request_id();
+    render_error(res, status, body, problem::current().as_ref());
+}
+
+/// 写入错误响应体：`problem` 为 `Some` 时转为 RFC 7807 问题详情，否则为统一响应结构
+pub fn render_error(
+    res: &mut Response,
+    status: StatusCode,
+    body: ApiResponse<Vec<FieldError>>,
+    problem: Option<&ProblemContext>,
+) {
+    match problem {
+        Some(problem) => res.render(problem.problem(status, body)),
+        None => {
+            res.status_code(status);
+            res.render(Json(body));
+        }
+    }
 }
 
 #[macro_export]
@@ -72,6 +106,177 @@ macro_rules! success {
     };
 }
 
-pub fn _inner_success<T: Serialize + ToSchema + Send>(data: Option<T>) -> CommonResult<T> {
-    Ok(ApiResponse::ok(data))
+impl<T: Serialize + ToSchema + Send> From<T> for ApiResponse<T> {
+    fn from(data: T) -> Self {
+        Self::ok(Some(data))
+    }
+}
+
+impl<T: Serialize + ToSchema + Send> Scribe for ApiResponse<T> {
+    fn render(self, res: &mut Response) {
+        write_json_response(res, self);
+    }
+}
+
+impl<T> EndpointOutRegister for ApiResponse<T>
+where
+    T: Serialize + ToSchema + Send,
+    Self: ToSchema,
+{
+    fn register(components: &mut Components, operation: &mut Operation) {
+        let schema = Self::to_schema(components);
+        operation.responses.insert(
+            StatusCode::OK.as_str(),
+            salvo::oapi::Response::new("成功；业务异常时 `code` 非 0")
+                .add_content("application/json", Content::new(schema)),
+        );
+    }
+}
+
+impl Scribe for ApiError {
+    fn render(self, res: &mut Response) {
+        write_error_response(res, self);
+    }
+}
+
+impl EndpointOutRegister for ApiError {
+    /// 登记处理器及 Service 级中间件（请求超时、请求体大小限制、限流）可能返回的错误状态码，
+    /// 响应体为统一响应结构或 RFC 7807 问题详情。401/403 由 [`complete_error_responses`]
+    /// 按接口是否声明 `oapi_security` 登记；409 仅在处理器声明时登记
+    fn register(components: &mut Components, operation: &mut Operation) {
+        let envelope = ApiResponse::<Vec<FieldError>>::to_schema(components);
+        let problem = ProblemDetails::to_schema(components);
+        for (status, description) in [
+            (StatusCode::BAD_REQUEST, "参数校验失败，`data` 为字段级错误"),
+            (StatusCode::NOT_FOUND, "资源不存在"),
+            (StatusCode::REQUEST_TIMEOUT, "请求超时"),
+            (StatusCode::PAYLOAD_TOO_LARGE, "请求体超出大小限制"),
+            (StatusCode::TOO_MANY_REQUESTS, "请求过于频繁"),
+            (StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误"),
+        ] {
+            if !operation.responses.contains_key(status.as_str()) {
+                operation.responses.insert(
+                    status.as_str(),
+                    error_response(description, &envelope, &problem),
+                );
+            }
+        }
+    }
+}
+
+/// 补全各接口的错误响应，需在 `merge_router` 之后调用（`oapi_security` 在登记响应之后才合并到接口）：
+/// 声明了安全要求的接口登记 401/403；处理器通过 `#[endpoint(responses((status_code = 409, ...)))]`
+/// 等自行声明、未指定响应体的错误状态码补充统一响应结构
+pub fn complete_error_responses(doc: &mut OpenApi) {
+    let envelope = ApiResponse::<Vec<FieldError>>::to_schema(&mut doc.components);
+    let problem = ProblemDetails::to_schema(&mut doc.components);
+    let global_security = !doc.security.is_empty();
+    for path in doc.paths.values_mut() {
+        for operation in path.operations.values_mut() {
+            if global_security || !operation.securities.is_empty() {
+                for (status, description) in [
+                    (StatusCode::UNAUTHORIZED, "未认证或令牌无效"),
+                    (StatusCode::FORBIDDEN, "禁止访问"),
+                ] {
+                    if !operation.responses.contains_key(status.as_str()) {
+                        operation.responses.insert(
+                            status.as_str(),
+                            error_response(description, &envelope, &problem),
+                        );
+                    }
+                }
+            }
+            for (status, response) in operation.responses.iter_mut() {
+                if let RefOr::Type(response) = response
+                    && response.contents.is_empty()
+                    && status.starts_with(['4', '5'])
+                {
+                    *response = error_response(&response.description, &envelope, &problem);
+                }
+            }
+        }
+    }
+}
+
+fn error_response(
+    description: &str,
+    envelope: &RefOr<Schema>,
+    problem: &RefOr<Schema>,
+) -> salvo::oapi::Response {
+    salvo::oapi::Response::new(description)
+        .add_content("application/json", Content::new(envelope.clone()))
+        .add_content(PROBLEM_JSON, Content::new(problem.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::oapi::OpenApi;
+    use salvo::oapi::endpoint;
+    use salvo::test::{ResponseExt, TestClient};
+    use serde_json::Value;
+
+    #[endpoint]
+    async fn double(req: &mut Request) -> CommonResult<i64> {
+        let n: i64 = crate::extract::extract_path_param(req, "n")?;
+        match n {
+            0 => Err(ApiError::Forbidden(String::from("无权限"))),
+            n => Ok((n * 2).into()),
+        }
+    }
+
+    /// 需登录、可能冲突的接口
+    #[endpoint(responses((status_code = 409, description = "账号已存在")))]
+    async fn create() -> CommonResult<()> {
+        Ok(ApiResponse::ok(None))
+    }
+
+    #[tokio::test]
+    async fn test_common_result() {
+        let router = Router::with_path("double/{n}").get(double);
+        let secured = Router::with_path("users")
+            .oapi_security(
+                salvo::oapi::SecurityRequirement::new::<&str, [&str; 0], &str>("bearer_auth", []),
+            )
+            .post(create);
+        let mut doc = OpenApi::new("test", "1.0")
+            .merge_router(&router)
+            .merge_router(&secured);
+        complete_error_responses(&mut doc);
+        let doc = serde_json::to_value(&doc).unwrap();
+        let statuses = |path: &str, method: &str| {
+            let responses = doc["paths"][path][method]["responses"].as_object().unwrap();
+            responses.keys().cloned().collect::<Vec<_>>()
+        };
+        // 公开接口不登记 401/403，未声明冲突的接口不登记 409
+        assert_eq!(
+            statuses("/double/{n}", "get"),
+            ["200", "400", "404", "408", "413", "429", "500"]
+        );
+        assert_eq!(
+            statuses("/users", "post"),
+            [
+                "200", "400", "401", "403", "404", "408", "409", "413", "429", "500"
+            ]
+        );
+        let responses = &doc["paths"]["/users"]["post"]["responses"];
+        assert!(responses["400"]["content"][PROBLEM_JSON].is_object());
+        assert_eq!(responses["409"]["description"], "账号已存在");
+        assert!(responses["409"]["content"]["application/json"].is_object());
+
+        let service = Service::new(router);
+        let mut res = TestClient::get("http://127.0.0.1:8080/double/21")
+            .send(&service)
+            .await;
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["code"], 0);
+        assert_eq!(body["data"], 42);
+
+        let mut res = TestClient::get("http://127.0.0.1:8080/double/0")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["code"], crate::error::common::FORBIDDEN.code);
+    }
 }
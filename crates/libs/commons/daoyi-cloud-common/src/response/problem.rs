@@ -0,0 +1,231 @@
+//! RFC 7807 问题详情（`application/problem+json`）错误响应，通过 `server.error_response` 按配置或内容协商启用，
+//! 默认仍使用统一响应结构 `ApiResponse`。
+
+use crate::conf::{ErrorFormat, ErrorResponseConfig};
+use crate::error::FieldError;
+use crate::response::ApiResponse;
+use salvo::http::header::{ACCEPT, CONTENT_TYPE};
+use salvo::http::{HeaderValue, StatusCode};
+use salvo::oapi::ToSchema;
+use salvo::prelude::*;
+use serde::Serialize;
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+tokio::task_local! {
+    static CURRENT_PROBLEM: ProblemContext;
+}
+
+/// 使用 RFC 7807 格式的请求的上下文，由 [`ProblemHandler`] 写入
+#[derive(Debug, Clone)]
+pub struct ProblemContext {
+    type_base: &'static str,
+    /// 请求路径，作为 `instance`
+    instance: String,
+}
+
+impl ProblemContext {
+    /// 由统一响应结构转换；业务异常的 HTTP 状态码为 200，转换时改为 400
+    pub fn problem(
+        &self,
+        status: StatusCode,
+        body: ApiResponse<Vec<FieldError>>,
+    ) -> ProblemDetails {
+        let status = if status.is_client_error() || status.is_server_error() {
+            status
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        ProblemDetails {
+            type_uri: format!("{}{}", self.type_base, body.code),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail: body.msg,
+            instance: self.instance.clone(),
+            code: body.code,
+            request_id: body.request_id,
+            errors: body.data.unwrap_or_default(),
+        }
+    }
+}
+
+/// 当前请求使用 RFC 7807 格式时返回其上下文
+pub fn current() -> Option<ProblemContext> {
+    CURRENT_PROBLEM.try_with(Clone::clone).ok()
+}
+
+/// RFC 7807 问题详情，`code`、`request_id`、`errors` 为扩展成员
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProblemDetails {
+    /// 问题类型，`type_base` 后接错误码
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    /// HTTP 状态码的标准描述
+    pub title: String,
+    pub status: u16,
+    /// 当前语言的提示信息
+    pub detail: String,
+    /// 请求路径
+    pub instance: String,
+    /// 错误码，同统一响应结构的 `code`
+    pub code: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// 字段级校验错误
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+impl Scribe for ProblemDetails {
+    fn render(self, res: &mut Response) {
+        match serde_json::to_vec(&self) {
+            Ok(bytes) => {
+                res.status_code(StatusCode::from_u16(self.status).unwrap_or_default());
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+                let _ = res.write_body(bytes);
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "Failed to serialize problem details");
+                res.render(StatusError::internal_server_error());
+            }
+        }
+    }
+}
+
+/// `Accept` 中是否接受 `application/problem+json`（`q=0` 除外）
+fn accepts_problem(req: &Request) -> bool {
+    req.headers()
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|item| {
+            let mut parts = item.split(';');
+            let media_type = parts.next().unwrap_or_default().trim();
+            let rejected = parts.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    .is_some_and(|q| q <= 0.0)
+            });
+            media_type.eq_ignore_ascii_case(PROBLEM_JSON) && !rejected
+        })
+}
+
+/// 错误响应格式中间件：按配置或请求的 `Accept` 决定是否使用 RFC 7807 格式，
+/// 需加在 Service 上，紧随语言中间件之后；同时写入 Depot 供 Catcher 使用。
+pub struct ProblemHandler {
+    config: &'static ErrorResponseConfig,
+}
+
+impl ProblemHandler {
+    pub fn new(config: &'static ErrorResponseConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[handler]
+impl ProblemHandler {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let enabled = match self.config.format() {
+            ErrorFormat::Envelope => false,
+            ErrorFormat::Problem => true,
+            ErrorFormat::Negotiate => accepts_problem(req),
+        };
+        if !enabled {
+            ctrl.call_next(req, depot, res).await;
+            return;
+        }
+        let context = ProblemContext {
+            type_base: self.config.type_base(),
+            instance: req.uri().path().to_string(),
+        };
+        depot.inject(context.clone());
+        CURRENT_PROBLEM
+            .scope(context, ctrl.call_next(req, depot, res))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiError;
+    use crate::response::write_error_response;
+    use crate::server::access_log::AccessLog;
+    use crate::server::catcher::create_catcher;
+    use salvo::test::{ResponseExt, TestClient};
+    use serde_json::Value;
+
+    #[handler]
+    async fn invalid(res: &mut Response) {
+        write_error_response(
+            res,
+            ApiError::Validation {
+                message: String::from("[name]不能为空"),
+                errors: vec![FieldError {
+                    field: String::from("name"),
+                    code: String::from("required"),
+                    message: String::from("不能为空"),
+                    params: Default::default(),
+                }],
+            },
+        );
+    }
+
+    fn service(format: &str) -> Service {
+        let config: ErrorResponseConfig =
+            serde_json::from_value(serde_json::json!({ "format": format })).unwrap();
+        Service::new(Router::with_path("invalid").get(invalid))
+            .catcher(create_catcher())
+            .hoop(AccessLog::default())
+            .hoop(ProblemHandler::new(Box::leak(Box::new(config))))
+    }
+
+    #[tokio::test]
+    async fn test_problem_details() {
+        let negotiate = service("negotiate");
+        let mut res = TestClient::get("http://127.0.0.1:8080/invalid")
+            .add_header(
+                ACCEPT,
+                "application/problem+json, application/json;q=0.9",
+                true,
+            )
+            .send(&negotiate)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+        assert_eq!(res.headers()[CONTENT_TYPE], PROBLEM_JSON);
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["type"], "urn:daoyi-cloud:error:1001");
+        assert_eq!(body["title"], "Bad Request");
+        assert_eq!(body["status"], 400);
+        assert_eq!(body["instance"], "/invalid");
+        assert_eq!(body["errors"][0]["field"], "name");
+        assert!(body["request_id"].is_string());
+
+        // 未协商时仍为统一响应结构
+        let mut res = TestClient::get("http://127.0.0.1:8080/invalid")
+            .send(&negotiate)
+            .await;
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["code"], 1001);
+        assert_eq!(body["data"][0]["field"], "name");
+
+        // Catcher 渲染的框架层错误
+        let mut res = TestClient::get("http://127.0.0.1:8080/missing")
+            .send(&service("problem"))
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        let body: Value = res.take_json().await.unwrap();
+        assert_eq!(body["type"], "urn:daoyi-cloud:error:1005");
+        assert_eq!(body["instance"], "/missing");
+    }
+}
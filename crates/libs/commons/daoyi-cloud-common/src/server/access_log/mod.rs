@@ -0,0 +1,303 @@
+use crate::auth::jwt::Principal;
+use crate::server::latency::Latency;
+use crate::telemetry;
+use crate::utils::id_utils;
+use anyhow::Context;
+use salvo::http::HeaderValue;
+use salvo::http::header::HeaderName;
+use salvo::prelude::*;
+use std::net::IpAddr;
+use std::time::Instant;
+use tracing::Instrument;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+/// 未登录时标识租户的请求头
+pub const TENANT_ID_HEADER: &str = "tenant-id";
+const REQUEST_ID_MAX_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// 当前请求的请求 ID，由 [`AccessLog`] 写入请求头
+pub fn request_id(req: &Request) -> Option<&str> {
+    req.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// 处理当前请求的任务中的请求 ID，用于无法访问 `Request` 的场景；新 spawn 的任务中不可用
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// 访问日志中间件：分配或沿用 `X-Request-Id`，请求处理期间的日志都带上该 ID，
+/// 结束后输出一条结构化访问日志并在响应头中回写请求 ID。需作为 Service 的第一个 hoop。
+#[derive(Debug, Default)]
+pub struct AccessLog {
+    trusted_proxies: TrustedProxies,
+}
+
+impl AccessLog {
+    /// 来自 `trusted_proxies` 的请求才按转发请求头记录客户端 IP
+    pub fn new(trusted_proxies: TrustedProxies) -> Self {
+        Self { trusted_proxies }
+    }
+}
+
+/// 可信反向代理的地址或网段，如 `10.0.0.0/8`、`::1`
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<(IpAddr, u32)>);
+
+impl TrustedProxies {
+    pub fn new(proxies: &[String]) -> anyhow::Result<Self> {
+        proxies
+            .iter()
+            .map(|proxy| {
+                let (ip, prefix) = proxy.split_once('/').unwrap_or((proxy, ""));
+                let ip = ip
+                    .trim()
+                    .parse::<IpAddr>()
+                    .with_context(|| format!("Invalid trusted proxy: {proxy}"))?;
+                let width = if ip.is_ipv4() { 32 } else { 128 };
+                let prefix = match prefix.trim() {
+                    "" => width,
+                    prefix => prefix
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|prefix| *prefix <= width)
+                        .with_context(|| format!("Invalid trusted proxy: {proxy}"))?,
+                };
+                Ok((ip, prefix))
+            })
+            .collect::<anyhow::Result<_>>()
+            .map(Self)
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let (ip, width) = ip_bits(ip.to_canonical());
+        self.0.iter().any(|(network, prefix)| {
+            let (network, network_width) = ip_bits(*network);
+            network_width == width && (*prefix == 0 || (network ^ ip) >> (width - prefix) == 0)
+        })
+    }
+}
+
+fn ip_bits(ip: IpAddr) -> (u128, u32) {
+    match ip {
+        IpAddr::V4(ip) => (u128::from(u32::from(ip)), 32),
+        IpAddr::V6(ip) => (u128::from(ip), 128),
+    }
+}
+
+#[handler]
+impl AccessLog {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let start = Instant::now();
+        let request_id = incoming_request_id(req).unwrap_or_else(id_utils::xid);
+        let header = HeaderValue::from_str(&request_id).expect("request id is a valid header");
+        req.headers_mut().insert(REQUEST_ID_HEADER, header.clone());
+
+        // 路由在 hoop 执行前已匹配完成，此时即可取得路由模板
+        let route = match req.matched_path() {
+            "" if res.status_code == Some(StatusCode::NOT_FOUND) => String::new(),
+            path => format!("/{path}"),
+        };
+        let span = if telemetry::enabled() {
+            let span = tracing::info_span!(
+                "request",
+                request_id = %request_id,
+                otel.name = %format_args!("{} {}", req.method(), route),
+                otel.kind = "server",
+                otel.status_code = tracing::field::Empty,
+                http.request.method = %req.method(),
+                http.route = route,
+                http.response.status_code = tracing::field::Empty,
+            );
+            telemetry::set_parent(&span, req.headers());
+            span
+        } else {
+            tracing::info_span!("request", request_id = %request_id)
+        };
+        CURRENT_REQUEST_ID
+            .scope(
+                request_id.clone(),
+                ctrl.call_next(req, depot, res).instrument(span.clone()),
+            )
+            .await;
+        res.headers_mut().insert(REQUEST_ID_HEADER, header);
+
+        let status = res.status_code.unwrap_or(StatusCode::OK).as_u16();
+        span.record("http.response.status_code", status);
+        if status >= 500 {
+            span.record("otel.status_code", "ERROR");
+        }
+        let principal = req.extensions().get::<Principal>();
+        let user_id = principal.map(|p| p.id);
+        let tenant_id = tenant_id(req, principal);
+        let _enter = span.enter();
+        tracing::info!(
+            target: "access_log",
+            method = %req.method(),
+            path = req.uri().path(),
+            route,
+            status,
+            latency = %Latency::from(start.elapsed()),
+            client_ip = client_ip(req, &self.trusted_proxies),
+            user_id,
+            tenant_id,
+            "{} {} {}",
+            req.method(),
+            req.uri().path(),
+            status,
+        );
+    }
+}
+
+/// 沿用上游传入的请求 ID，仅接受长度受限的可见字符，防止日志注入
+fn incoming_request_id(req: &Request) -> Option<String> {
+    request_id(req)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= REQUEST_ID_MAX_LEN
+                && id
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"-_.:".contains(&b))
+        })
+        .map(String::from)
+}
+
+/// 租户 ID，优先取认证信息中的租户，其次为 `tenant-id` 请求头；请求头可由客户端任意设置，
+/// 仅用于日志等展示场景，不可用于鉴权或限流
+pub fn tenant_id(req: &Request, principal: Option<&Principal>) -> Option<String> {
+    principal.map(|p| p.tenant_id.to_string()).or_else(|| {
+        req.headers()
+            .get(TENANT_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    })
+}
+
+/// 客户端 IP。连接来自可信代理时取 `X-Forwarded-For` 中从右向左第一个不可信的地址
+/// （均可信时取最左侧），其次为 `X-Real-IP`；否则为连接的对端地址，忽略转发请求头
+pub fn client_ip(req: &Request, trusted_proxies: &TrustedProxies) -> String {
+    let peer = req
+        .remote_addr()
+        .as_ipv4()
+        .map(|addr| IpAddr::V4(*addr.ip()))
+        .or_else(|| {
+            req.remote_addr()
+                .as_ipv6()
+                .map(|addr| IpAddr::V6(*addr.ip()))
+        });
+    let Some(peer) = peer.filter(|ip| trusted_proxies.contains(*ip)) else {
+        return peer.map(|ip| ip.to_string()).unwrap_or_default();
+    };
+    let forwarded = req
+        .headers()
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect::<Vec<_>>();
+    // 代理逐级追加在右侧，左侧的地址可由客户端伪造，遇到无法解析的地址即停止
+    let chain = forwarded
+        .iter()
+        .rev()
+        .map_while(|ip| ip.trim().parse::<IpAddr>().ok())
+        .collect::<Vec<_>>();
+    chain
+        .iter()
+        .find(|ip| !trusted_proxies.contains(**ip))
+        .or(chain.last())
+        .copied()
+        .or_else(|| {
+            req.headers()
+                .get("x-real-ip")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|ip| ip.trim().parse().ok())
+        })
+        .unwrap_or(peer)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+
+    #[handler]
+    async fn echo(req: &mut Request) -> String {
+        request_id(req).unwrap_or_default().to_string()
+    }
+
+    fn service() -> Service {
+        Service::new(Router::with_path("echo").get(echo)).hoop(AccessLog::default())
+    }
+
+    #[tokio::test]
+    async fn test_assign_request_id() {
+        let mut res = TestClient::get("http://127.0.0.1:8080/echo")
+            .send(&service())
+            .await;
+        let header = res.headers().get(REQUEST_ID_HEADER).cloned().unwrap();
+        let body = res.take_string().await.unwrap();
+        assert!(!body.is_empty());
+        assert_eq!(header, body.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_propagate_request_id() {
+        let mut res = TestClient::get("http://127.0.0.1:8080/echo")
+            .add_header(REQUEST_ID_HEADER, "upstream-id-1", true)
+            .send(&service())
+            .await;
+        assert_eq!(
+            res.headers().get(REQUEST_ID_HEADER).unwrap(),
+            "upstream-id-1"
+        );
+        assert_eq!(res.take_string().await.unwrap(), "upstream-id-1");
+
+        let res = TestClient::get("http://127.0.0.1:8080/echo")
+            .add_header(REQUEST_ID_HEADER, "bad id\twith spaces", true)
+            .send(&service())
+            .await;
+        assert_ne!(
+            res.headers().get(REQUEST_ID_HEADER).unwrap(),
+            "bad id\twith spaces"
+        );
+    }
+
+    #[test]
+    fn test_trusted_proxies() {
+        let proxies = TrustedProxies::new(&["10.0.0.0/8".to_string(), "::1".to_string()]).unwrap();
+        assert!(proxies.contains("10.1.2.3".parse().unwrap()));
+        assert!(proxies.contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(proxies.contains("::1".parse().unwrap()));
+        assert!(!proxies.contains("11.0.0.1".parse().unwrap()));
+        assert!(!proxies.contains("::2".parse().unwrap()));
+        assert!(TrustedProxies::new(&["10.0.0.0/33".to_string()]).is_err());
+        assert!(TrustedProxies::new(&["proxy".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_tenant_id() {
+        let mut req = Request::new();
+        assert_eq!(tenant_id(&req, None), None);
+        req.headers_mut()
+            .insert(TENANT_ID_HEADER, HeaderValue::from_static("7"));
+        assert_eq!(tenant_id(&req, None).as_deref(), Some("7"));
+        let principal = Principal {
+            tenant_id: 1,
+            id: 2,
+            name: String::from("admin"),
+        };
+        assert_eq!(tenant_id(&req, Some(&principal)).as_deref(), Some("1"));
+    }
+}
@@ -0,0 +1,135 @@
+//! 处理器 panic 隔离：panic 转为带错误 ID 的 500 响应，panic 信息与调用栈按错误 ID 记录日志。
+//! 依赖栈展开，以 `panic = "abort"` 编译时 panic 仍会终止进程，仅能记录日志。
+
+use crate::error::{common, internal_error_message};
+use crate::response::{ApiResponse, problem, render_error};
+use crate::server::access_log::request_id;
+use crate::utils::id_utils;
+use salvo::prelude::*;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, PanicHookInfo};
+use std::sync::Once;
+use std::task::Poll;
+
+thread_local! {
+    /// 当前线程最近一次 panic 的错误 ID，由 panic hook 写入
+    static PANIC_ERROR_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// 是否以 `panic = "abort"` 编译
+pub const PANIC_ABORT: bool = cfg!(panic = "abort");
+
+/// 安装 panic hook：为每次 panic 生成错误 ID，并将 panic 信息与调用栈写入日志（替代默认的 stderr 输出）
+pub fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| std::panic::set_hook(Box::new(log_panic)));
+}
+
+fn log_panic(info: &PanicHookInfo<'_>) {
+    let error_id = id_utils::xid();
+    let location = info
+        .location()
+        .map(|location| location.to_string())
+        .unwrap_or_default();
+    let message = panic_message(info.payload());
+    let backtrace = Backtrace::force_capture();
+    tracing::error!(
+        target: "panic",
+        error_id,
+        location,
+        "panic: {}\n{}",
+        message,
+        backtrace
+    );
+    if PANIC_ABORT {
+        // 进程即将终止，异步日志可能来不及写出
+        eprintln!("panic [{error_id}] at {location}: {message}\n{backtrace}");
+    }
+    PANIC_ERROR_ID.set(Some(error_id));
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
+
+/// panic 隔离中间件，需加在 Service 上，位于访问日志、指标之后
+pub struct CatchPanic;
+
+#[handler]
+impl CatchPanic {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let result = {
+            let mut next = std::pin::pin!(ctrl.call_next(req, depot, res));
+            std::future::poll_fn(|cx| {
+                match std::panic::catch_unwind(AssertUnwindSafe(|| next.as_mut().poll(cx))) {
+                    Ok(Poll::Ready(_)) => Poll::Ready(Ok(())),
+                    Ok(Poll::Pending) => Poll::Pending,
+                    Err(payload) => Poll::Ready(Err(payload)),
+                }
+            })
+            .await
+        };
+        let Err(payload) = result else {
+            return;
+        };
+        // 未安装 hook 时在此生成错误 ID 并记录
+        let error_id = PANIC_ERROR_ID.take().unwrap_or_else(|| {
+            let error_id = id_utils::xid();
+            tracing::error!(
+                target: "panic",
+                error_id,
+                "panic: {}",
+                panic_message(payload.as_ref())
+            );
+            error_id
+        });
+        crate::metrics::counter!("http_server_panics_total").increment(1);
+        ctrl.skip_rest();
+        // panic 时可能已写入部分响应体，丢弃后再输出
+        res.take_body();
+        let mut body = ApiResponse::err(common::INTERNAL.code, internal_error_message(&error_id));
+        body.request_id = request_id(req).map(String::from);
+        render_error(
+            res,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            body,
+            problem::current().as_ref(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+
+    #[handler]
+    async fn boom() -> &'static str {
+        tokio::task::yield_now().await;
+        panic!("boom");
+    }
+
+    #[tokio::test]
+    async fn test_catch_panic() {
+        let service = Service::new(Router::with_path("boom").get(boom)).hoop(CatchPanic);
+        let mut res = TestClient::get("http://127.0.0.1:8080/boom")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+        let body: serde_json::Value = res.take_json().await.unwrap();
+        assert!(body["msg"].as_str().unwrap().contains("错误 ID："));
+    }
+}
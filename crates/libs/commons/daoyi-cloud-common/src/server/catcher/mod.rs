@@ -0,0 +1,154 @@
+use crate::error::{ApiError, FieldError, common};
+use crate::i18n;
+use crate::response::problem::ProblemContext;
+use crate::response::{ApiResponse, render_error};
+use crate::server::access_log::request_id;
+use salvo::catcher::Catcher;
+use salvo::http::ResBody;
+use salvo::prelude::*;
+
+/// 框架层错误（未匹配路由、方法不允许、请求体解析失败等）统一渲染为带请求 ID 的 `ApiResponse`
+pub fn create_catcher() -> Catcher {
+    Catcher::default().hoop(catch_error)
+}
+
+#[handler]
+async fn catch_error(
+    req: &mut Request,
+    depot: &mut Depot,
+    res: &mut Response,
+    ctrl: &mut FlowCtrl,
+) {
+    let status = res.status_code.unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let error = match &res.body {
+        ResBody::Error(e) => Some(e),
+        _ => None,
+    };
+    // Catcher 在 Service 的 hoop 之外执行，需重新解析请求语言
+    let locale = i18n::resolve(req).await;
+    let mut body = i18n::scope(locale, async { to_api_response(status, error) }).await;
+    body.request_id = request_id(req).map(String::from);
+    let problem = depot.obtain::<ProblemContext>().ok();
+    render_error(res, status, body, problem);
+    ctrl.skip_rest();
+}
+
+fn to_api_response(
+    status: StatusCode,
+    error: Option<&StatusError>,
+) -> ApiResponse<Vec<FieldError>> {
+    let brief = error
+        .map(|e| e.brief.as_str())
+        .filter(|brief| !brief.is_empty());
+    let error = match status {
+        StatusCode::NOT_FOUND => ApiError::NotFound,
+        StatusCode::METHOD_NOT_ALLOWED => ApiError::MethodNotAllowed,
+        StatusCode::REQUEST_TIMEOUT => ApiError::RequestTimeout,
+        StatusCode::PAYLOAD_TOO_LARGE => ApiError::PayloadTooLarge,
+        StatusCode::TOO_MANY_REQUESTS => ApiError::TooManyRequests,
+        StatusCode::HTTP_VERSION_NOT_SUPPORTED => ApiError::HttpVersionNotSupported,
+        // 服务端错误按内部错误处理，详情只记录日志
+        status if status.is_server_error() => {
+            let cause = error
+                .and_then(|e| e.cause.as_ref())
+                .map(|cause| format!(": {cause}"))
+                .unwrap_or_default();
+            ApiError::Internal(anyhow::anyhow!(
+                "{} {}{}",
+                status,
+                brief.unwrap_or_default(),
+                cause
+            ))
+        }
+        status => {
+            let message = brief
+                .or_else(|| status.canonical_reason())
+                .map(String::from)
+                .unwrap_or_else(|| status.to_string());
+            return ApiResponse::err(common::BAD_REQUEST.code, message);
+        }
+    };
+    error.to_api_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::access_log::{AccessLog, REQUEST_ID_HEADER};
+    use salvo::test::{ResponseExt, TestClient};
+    use serde_json::Value;
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    #[handler]
+    async fn fail() -> Result<(), StatusError> {
+        Err(StatusError::internal_server_error().brief("connection refused: 10.0.0.1:3306"))
+    }
+
+    #[handler]
+    async fn forbidden(res: &mut Response) {
+        crate::response::write_error_response(res, ApiError::Forbidden(String::from("无权限")));
+    }
+
+    fn service() -> Service {
+        let router = Router::new()
+            .push(Router::with_path("hello").get(hello))
+            .push(Router::with_path("fail").get(fail))
+            .push(Router::with_path("forbidden").get(forbidden));
+        Service::new(router)
+            .catcher(create_catcher())
+            .hoop(AccessLog::default())
+    }
+
+    async fn read_error(res: &mut Response) -> (StatusCode, Value) {
+        let body: Value = res.take_json().await.unwrap();
+        let header = res
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert_eq!(body["request_id"], header);
+        (res.status_code.unwrap(), body)
+    }
+
+    #[tokio::test]
+    async fn test_render_errors() {
+        let service = service();
+        let mut res = TestClient::get("http://127.0.0.1:8080/missing")
+            .send(&service)
+            .await;
+        let (status, body) = read_error(&mut res).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["msg"], ApiError::NotFound.to_string());
+        assert_eq!(body["code"], common::NOT_FOUND.code);
+
+        let mut res = TestClient::post("http://127.0.0.1:8080/hello")
+            .send(&service)
+            .await;
+        let (status, body) = read_error(&mut res).await;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(body["msg"], ApiError::MethodNotAllowed.to_string());
+
+        let mut res = TestClient::get("http://127.0.0.1:8080/fail")
+            .send(&service)
+            .await;
+        let (status, body) = read_error(&mut res).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        let msg = body["msg"].as_str().unwrap();
+        assert!(msg.starts_with("服务器内部错误，错误 ID："));
+        assert!(!msg.contains("10.0.0.1"));
+        assert_eq!(body["code"], common::INTERNAL.code);
+
+        // 处理器写入的错误响应同样带请求 ID
+        let mut res = TestClient::get("http://127.0.0.1:8080/forbidden")
+            .send(&service)
+            .await;
+        let (status, body) = read_error(&mut res).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], common::FORBIDDEN.code);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::conf::CorsConfig;
+use crate::error::ApiError;
+use crate::i18n;
+use crate::response::write_error_response;
+use anyhow::Context;
+use regex::Regex;
+use salvo::cors::{
+    AllowHeaders, AllowMethods, AllowOrigin, Any, Cors, CorsHandler, ExposeHeaders,
+};
+use salvo::http::header::{ACCESS_CONTROL_REQUEST_METHOD, HeaderName, ORIGIN};
+use salvo::http::Method;
+use salvo::prelude::*;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// 按配置生效的 CORS 中间件，预检请求的来源不被允许时直接返回 403
+pub struct CorsPolicy {
+    matcher: Arc<OriginMatcher>,
+    inner: CorsHandler,
+}
+
+struct OriginMatcher {
+    any: bool,
+    origins: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl OriginMatcher {
+    fn matches(&self, origin: &str) -> bool {
+        self.any
+            || self.origins.iter().any(|o| o == origin)
+            || self.patterns.iter().any(|p| p.is_match(origin))
+    }
+}
+
+impl CorsPolicy {
+    pub fn new(config: &CorsConfig) -> anyhow::Result<Self> {
+        let matcher = Arc::new(OriginMatcher {
+            any: config.allow_origins().iter().any(|o| o == "*"),
+            origins: config.allow_origins().to_vec(),
+            patterns: config
+                .allow_origin_patterns()
+                .iter()
+                .map(|p| origin_pattern(p))
+                .collect::<anyhow::Result<_>>()?,
+        });
+        let wildcard = matcher.any
+            || config.allow_methods().contains(&"*")
+            || config.allow_headers().contains(&"*")
+            || config.expose_headers().iter().any(|h| h == "*");
+        if config.allow_credentials() && wildcard {
+            anyhow::bail!("CORS: `allow_credentials` cannot be combined with `*`");
+        }
+
+        let origin_matcher = matcher.clone();
+        let allow_origin = AllowOrigin::dynamic(move |origin, _, _| {
+            let origin = origin?;
+            origin_matcher
+                .matches(origin.to_str().ok()?)
+                .then(|| origin.clone())
+        });
+        let mut cors = Cors::new()
+            .allow_origin(allow_origin)
+            .allow_methods(allow_methods(&config.allow_methods())?)
+            .allow_headers(allow_headers(&config.allow_headers())?)
+            .expose_headers(expose_headers(config.expose_headers())?)
+            .max_age(config.max_age());
+        if config.allow_credentials() {
+            cors = cors.allow_credentials(true);
+        }
+        Ok(Self {
+            matcher,
+            inner: cors.into_handler(),
+        })
+    }
+}
+
+#[handler]
+impl CorsPolicy {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let rejected = req.method() == Method::OPTIONS
+            && req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+            && req
+                .headers()
+                .get(ORIGIN)
+                .is_some_and(|origin| !origin.to_str().is_ok_and(|o| self.matcher.matches(o)));
+        if rejected {
+            write_error_response(res, ApiError::Forbidden(i18n::t("cors.origin_forbidden", &[])));
+            ctrl.skip_rest();
+            return;
+        }
+        self.inner.handle(req, depot, res, ctrl).await;
+    }
+}
+
+/// 将 `*` 通配的来源模式转换为正则
+fn origin_pattern(pattern: &str) -> anyhow::Result<Regex> {
+    let regex = format!("^{}$", regex::escape(pattern).replace(r"\*", "[^/]*"));
+    Regex::new(&regex).with_context(|| format!("CORS: invalid origin pattern {pattern}"))
+}
+
+fn allow_methods(methods: &[&str]) -> anyhow::Result<AllowMethods> {
+    if methods.contains(&"*") {
+        return Ok(Any.into());
+    }
+    let methods = methods
+        .iter()
+        .map(|m| Method::from_str(&m.to_uppercase()))
+        .collect::<Result<Vec<_>, _>>()
+        .context("CORS: invalid method")?;
+    Ok(methods.into())
+}
+
+fn allow_headers(headers: &[&str]) -> anyhow::Result<AllowHeaders> {
+    if headers.contains(&"*") {
+        return Ok(Any.into());
+    }
+    Ok(header_names(headers)?.into())
+}
+
+fn expose_headers(headers: &[String]) -> anyhow::Result<ExposeHeaders> {
+    if headers.iter().any(|h| h == "*") {
+        return Ok(Any.into());
+    }
+    Ok(header_names(headers)?.into())
+}
+
+fn header_names<S: AsRef<str>>(headers: &[S]) -> anyhow::Result<Vec<HeaderName>> {
+    headers
+        .iter()
+        .map(|h| {
+            HeaderName::from_str(h.as_ref())
+                .with_context(|| format!("CORS: invalid header {}", h.as_ref()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::http::header::ACCESS_CONTROL_ALLOW_ORIGIN;
+    use salvo::test::{RequestBuilder, TestClient};
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    fn service(config: serde_json::Value) -> Service {
+        let config: CorsConfig = serde_json::from_value(config).unwrap();
+        let router = Router::with_path("hello").get(hello);
+        Service::new(router).hoop(CorsPolicy::new(&config).unwrap())
+    }
+
+    fn preflight(origin: &str) -> RequestBuilder {
+        TestClient::options("http://127.0.0.1:8080/hello")
+            .add_header(ORIGIN, origin, true)
+            .add_header(ACCESS_CONTROL_REQUEST_METHOD, "GET", true)
+    }
+
+    #[tokio::test]
+    async fn test_preflight_allowed_origin() {
+        let service = service(serde_json::json!({
+            "allow_origins": ["http://localhost:3000"],
+            "allow_origin_patterns": ["https://*.example.com"],
+        }));
+        for origin in ["http://localhost:3000", "https://admin.example.com"] {
+            let res = preflight(origin).send(&service).await;
+            assert_eq!(res.status_code, Some(StatusCode::NO_CONTENT));
+            assert_eq!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(), origin);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preflight_disallowed_origin() {
+        let service = service(serde_json::json!({
+            "allow_origins": ["http://localhost:3000"],
+            "allow_origin_patterns": ["https://*.example.com"],
+        }));
+        for origin in [
+            "http://evil.com",
+            "https://example.com.evil.com",
+            "https://a.example.com/x",
+        ] {
+            let res = preflight(origin).send(&service).await;
+            assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+            assert!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simple_request_disallowed_origin() {
+        let service = service(serde_json::json!({}));
+        let res = TestClient::get("http://127.0.0.1:8080/hello")
+            .add_header(ORIGIN, "http://evil.com", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    #[test]
+    fn test_credentials_with_wildcard() {
+        let config: CorsConfig = serde_json::from_value(serde_json::json!({
+            "allow_origins": ["*"],
+            "allow_credentials": true,
+        }))
+        .unwrap();
+        assert!(CorsPolicy::new(&config).is_err());
+    }
+}
@@ -3,6 +3,12 @@ use std::time::Duration;
 
 pub struct Latency(Duration);
 
+impl From<Duration> for Latency {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
 impl Display for Latency {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.0.as_millis() > 0 {
@@ -0,0 +1,167 @@
+use crate::conf::ServerConfig;
+use crate::conf::server::RouteLimit;
+use crate::error::ApiError;
+use crate::response::write_error_response;
+use salvo::http::header::{CONNECTION, HeaderValue};
+use salvo::http::{Body, Version};
+use salvo::prelude::*;
+use wax::{Glob, Program};
+
+/// 请求限制中间件：按路由应用请求超时、请求体大小限制，并在关闭 HTTP/2 时拒绝 HTTP/2 请求
+pub struct RequestLimiter {
+    config: &'static ServerConfig,
+    default: RouteLimit,
+    routes: Vec<(Glob<'static>, RouteLimit)>,
+}
+
+impl RequestLimiter {
+    /// 启动时编译 `routes` 路径模式，模式无效时返回错误
+    pub fn new(config: &'static ServerConfig) -> anyhow::Result<Self> {
+        let default = config.default_limit();
+        let routes = config
+            .routes()
+            .iter()
+            .map(|route| Ok((Glob::new(route.path())?, route.limit(default))))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            config,
+            default,
+            routes,
+        })
+    }
+
+    /// 第一条匹配路由的请求限制，均不匹配时使用全局配置
+    fn limit(&self, path: &str) -> RouteLimit {
+        self.routes
+            .iter()
+            .find(|(glob, _)| glob.is_match(path))
+            .map_or(self.default, |(_, limit)| *limit)
+    }
+}
+
+#[handler]
+impl RequestLimiter {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        if !self.config.http2() && req.version() == Version::HTTP_2 {
+            write_error_response(res, ApiError::HttpVersionNotSupported);
+            ctrl.skip_rest();
+            return;
+        }
+        let limit = self.limit(req.uri().path());
+        if let Some(max_body_size) = limit.max_body_size {
+            if req
+                .body()
+                .size_hint()
+                .upper()
+                .is_some_and(|size| size > max_body_size)
+            {
+                write_error_response(res, ApiError::PayloadTooLarge);
+                ctrl.skip_rest();
+                return;
+            }
+            // 无 Content-Length 的请求在读取请求体时再校验
+            req.set_secure_max_size(usize::try_from(max_body_size).unwrap_or(usize::MAX));
+        }
+        match limit.request_timeout {
+            Some(timeout) => {
+                tokio::select! {
+                    _ = ctrl.call_next(req, depot, res) => {},
+                    _ = tokio::time::sleep(timeout) => {
+                        res.headers_mut().insert(CONNECTION, HeaderValue::from_static("close"));
+                        write_error_response(res, ApiError::RequestTimeout);
+                        ctrl.skip_rest();
+                    }
+                }
+            }
+            None => {
+                ctrl.call_next(req, depot, res).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use salvo::test::{ResponseExt, TestClient};
+    use std::time::Duration;
+
+    #[handler]
+    async fn echo(req: &mut Request) -> String {
+        match req.payload().await {
+            Ok(body) => format!("{}", body.len()),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[handler]
+    async fn slow() -> &'static str {
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        "slow"
+    }
+
+    fn service() -> Service {
+        let config: ServerConfig = serde_json::from_value(serde_json::json!({
+            "request_timeout": 0,
+            "max_body_size": "16B",
+            "routes": [
+                { "path": "/upload", "max_body_size": "64B" },
+                { "path": "/slow", "request_timeout": 1 },
+            ],
+        }))
+        .unwrap();
+        let router = Router::new()
+            .hoop(RequestLimiter::new(Box::leak(Box::new(config))).unwrap())
+            .push(Router::with_path("echo").post(echo))
+            .push(Router::with_path("upload").post(echo))
+            .push(Router::with_path("slow").get(slow));
+        Service::new(router)
+    }
+
+    #[tokio::test]
+    async fn test_max_body_size() {
+        let service = service();
+        let mut res = TestClient::post("http://127.0.0.1:8080/echo")
+            .text("0123456789")
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "10");
+
+        let mut res = TestClient::post("http://127.0.0.1:8080/echo")
+            .text("0123456789".repeat(2))
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::PAYLOAD_TOO_LARGE));
+        assert!(res.take_string().await.unwrap().contains("请求体过大"));
+
+        let mut res = TestClient::post("http://127.0.0.1:8080/upload")
+            .text("0123456789".repeat(2))
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "20");
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let mut res = TestClient::get("http://127.0.0.1:8080/slow")
+            .send(&service())
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::REQUEST_TIMEOUT));
+        assert!(res.take_string().await.unwrap().contains("请求超时"));
+    }
+
+    #[test]
+    fn test_invalid_route_pattern() {
+        let config: ServerConfig = serde_json::from_value(serde_json::json!({
+            "routes": [{ "path": "/upload/[", "max_body_size": "64B" }],
+        }))
+        .unwrap();
+        assert!(RequestLimiter::new(Box::leak(Box::new(config))).is_err());
+    }
+}
@@ -1,72 +1,153 @@
+pub mod access_log;
+pub mod catch_panic;
+pub mod catcher;
+pub mod cors;
 pub mod latency;
+pub mod limit;
+pub mod rate_limit;
+pub mod tls;
 
-use crate::conf::ServerConfig;
-use salvo::cors::{Any, Cors};
-use salvo::oapi::OpenApi;
-use salvo::oapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityScheme};
+use crate::conf::{
+    AppConfig, HealthConfig, LoggingConfig, MetricsConfig, OpenApiConfig, ServerConfig,
+};
+use crate::i18n::LocaleHandler;
+use crate::metrics::RequestMetrics;
+use crate::response::problem::ProblemHandler;
+use crate::server::access_log::{AccessLog, TrustedProxies};
+use crate::server::catch_panic::CatchPanic;
+use crate::server::cors::CorsPolicy;
+use crate::server::limit::RequestLimiter;
+use crate::server::rate_limit::RateLimiter;
+use crate::server::tls::TlsReloader;
+use crate::shutdown;
+use anyhow::Context;
+use salvo::conn::tcp::{DynTcpAcceptor, DynTcpAcceptors};
+use salvo::http::request::set_global_secure_max_size;
 use salvo::prelude::*;
 use salvo::trailing_slash::{TrailingSlash, TrailingSlashAction};
-use salvo_oapi::scalar::Scalar;
-use salvo_oapi::swagger_ui::SwaggerUi;
-use std::time::Duration;
 
 pub struct AppServer {
     config: &'static ServerConfig,
+    openapi: &'static OpenApiConfig,
+    health: &'static HealthConfig,
+    metrics: &'static MetricsConfig,
+    logging: &'static LoggingConfig,
 }
 
 impl AppServer {
-    pub fn new(config: &'static ServerConfig) -> Self {
-        Self { config }
+    pub fn new(app_config: &'static AppConfig) -> Self {
+        Self {
+            config: app_config.server(),
+            openapi: app_config.openapi(),
+            health: app_config.health(),
+            metrics: app_config.metrics(),
+            logging: app_config.logging(),
+        }
     }
 
     pub async fn start(&self, router: Router) -> anyhow::Result<()> {
         let port = self.config.port();
 
-        // 创建 OpenAPI 文档
-        let doc = OpenApi::new("DaoYi Cloud API", "0.9.0")
-            .add_security_scheme(
-                "bearer_auth",
-                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer).bearer_format("JWT")),
-            )
-            .add_security_scheme(
-                "tenant_id",
-                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
-                    "tenant-id",
-                    "租户ID",
-                ))),
-            )
-            .merge_router(&router);
-
+        let openapi = self.openapi;
+        let mut router = router;
+        if openapi.enabled() {
+            // 文档需基于业务路由生成，必须在挂载文档路由之前创建
+            let docs = crate::openapi::create_router(openapi, &router);
+            router = router.push(docs);
+        }
+        if self.health.enabled() {
+            router = router.push(crate::health::create_router(self.health));
+        }
+        if self.metrics.enabled() {
+            router = router.push(crate::metrics::create_router(self.metrics));
+        }
+        let log_admin = self.logging.admin();
+        if log_admin.enabled() {
+            // 未配置访问保护时拒绝启动，避免管理端点裸露
+            let auth = log_admin
+                .auth()
+                .with_context(|| "logging.admin.auth must be configured when admin is enabled")?;
+            router = router.push(crate::logger::admin::create_router(log_admin, auth));
+        }
         let router = router
-            .push(doc.into_router("/api-docs/openapi.json"))
-            .push(
-                SwaggerUi::new("/swagger-ui/{_:.*}")
-                    .url("/api-docs/openapi.json")
-                    .into_router("/swagger-ui"),
-            )
-            .push(Scalar::new("/api-docs/openapi.json").into_router("/scalar"))
             .push(Router::with_path("/").get(index))
             .hoop(TrailingSlash::new(TrailingSlashAction::Remove));
 
-        // CORS 必须加到 Service 级别
-        let cors = Cors::new()
-            .allow_origin(Any)
-            .allow_methods(Any)
-            .allow_headers(Any)
-            .allow_credentials(false)
-            .max_age(Duration::from_secs(43200))
-            .into_handler();
-
-        let service = Service::new(router).hoop(cors);
+        set_global_secure_max_size(
+            usize::try_from(self.config.max_body_size()).unwrap_or(usize::MAX),
+        );
+        // 访问日志、指标、CORS 必须加到 Service 级别；访问日志需为第一个 hoop，
+        // 才能为后续 hoop 直接返回的响应也分配请求 ID，语言紧随其后以本地化这些响应，
+        // 错误格式再其后以决定这些响应的格式
+        let trusted_proxies = TrustedProxies::new(self.config.trusted_proxies())?;
+        let mut service = Service::new(router)
+            .catcher(catcher::create_catcher())
+            .hoop(AccessLog::new(trusted_proxies.clone()))
+            .hoop(LocaleHandler)
+            .hoop(ProblemHandler::new(self.config.error_response()));
+        if self.metrics.enabled() {
+            service = service.hoop(RequestMetrics);
+        }
+        if self.config.catch_panic() {
+            service = service.hoop(CatchPanic);
+        }
+        if self.config.cors().enabled() {
+            service = service.hoop(CorsPolicy::new(self.config.cors())?);
+        }
+        if self.config.rate_limit().enabled() {
+            service = service.hoop(RateLimiter::new(self.config.rate_limit(), trusted_proxies)?);
+        }
+        let service = service.hoop(RequestLimiter::new(self.config)?);
 
-        let listener = TcpListener::new(("0.0.0.0", port)).bind().await;
-        tracing::info!("listening on http://0.0.0.0:{}", port);
-        tracing::info!("Swagger UI: http://localhost:{}/swagger-ui", port);
-        tracing::info!("Scalar: http://localhost:{}/scalar", port);
+        let acceptor = self.bind().await?;
+        let scheme = if self.config.tls().is_some() { "https" } else { "http" };
+        if openapi.enabled() {
+            if let Some(path) = openapi.swagger_ui() {
+                tracing::info!("Swagger UI: {}://localhost:{}{}", scheme, port, path);
+            }
+            if let Some(path) = openapi.scalar() {
+                tracing::info!("Scalar: {}://localhost:{}{}", scheme, port, path);
+            }
+        }
 
-        salvo::Server::new(listener).serve(service).await;
+        let server = salvo::Server::new(acceptor);
+        let handle = server.handle();
+        let grace_period = self.config.shutdown().grace_period();
+        tokio::spawn(async move {
+            let signal = shutdown::wait_signal().await;
+            tracing::info!(
+                "Received {}, stop accepting connections and waiting up to {:?} for in-flight requests",
+                signal,
+                grace_period
+            );
+            shutdown::begin();
+            handle.stop_graceful(grace_period);
+        });
+        server.serve(service).await;
+        tracing::info!("Server stopped");
         Ok(())
     }
+
+    /// 绑定所有监听地址，启用 TLS 时每个地址都使用同一份可热加载的证书
+    async fn bind(&self) -> anyhow::Result<DynTcpAcceptors> {
+        let tls = self
+            .config
+            .tls()
+            .map(|tls| TlsReloader::new(tls, self.config.http2()))
+            .transpose()?;
+        let mut acceptors: Vec<Box<dyn DynTcpAcceptor>> = Vec::new();
+        for addr in self.config.bind_addrs()? {
+            let listener = TcpListener::new(addr);
+            let acceptor = match &tls {
+                Some(tls) => listener.rustls(tls.stream()).try_bind().await?.into_boxed(),
+                None => listener.try_bind().await?.into_boxed(),
+            };
+            let scheme = if tls.is_some() { "https" } else { "http" };
+            tracing::info!("listening on {}://{}", scheme, addr);
+            acceptors.push(acceptor);
+        }
+        Ok(DynTcpAcceptors::new(acceptors))
+    }
 }
 
 #[handler]
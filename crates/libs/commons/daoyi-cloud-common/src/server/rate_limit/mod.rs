@@ -0,0 +1,393 @@
+use crate::auth::jwt::middleware::principal;
+use crate::conf::RateLimitConfig;
+use crate::conf::server::{RateLimitKey, RateLimitRule};
+use crate::error::ApiError;
+use crate::response::write_error_response;
+use crate::server::access_log::{TrustedProxies, client_ip};
+use salvo::http::HeaderValue;
+use salvo::http::header::{HeaderName, RETRY_AFTER};
+use salvo::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use wax::{Glob, Program};
+
+const RATE_LIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATE_LIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATE_LIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+static STORE: OnceLock<Arc<dyn RateLimitStore>> = OnceLock::new();
+
+/// 配额：`period` 内最多 `limit` 次请求
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub limit: u32,
+    pub period: Duration,
+}
+
+/// 一次令牌获取的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    /// 剩余可用次数
+    pub remaining: u32,
+    /// 配额完全恢复所需时间
+    pub reset: Duration,
+    /// 被拒绝时距下一个可用令牌的时间
+    pub retry_after: Option<Duration>,
+}
+
+/// 限流计数存储，多实例部署时实现共享存储（如 Redis）并通过 [`set_store`] 注册
+#[salvo::async_trait]
+pub trait RateLimitStore: Send + Sync + 'static {
+    /// 为 `key` 获取一个令牌
+    async fn acquire(&self, key: &str, quota: Quota) -> anyhow::Result<RateLimitDecision>;
+}
+
+/// 替换默认的内存存储，需在服务启动前调用
+pub fn set_store(store: impl RateLimitStore) {
+    if STORE.set(Arc::new(store)).is_err() {
+        tracing::warn!("Rate limit store is already set");
+    }
+}
+
+fn store() -> Arc<dyn RateLimitStore> {
+    STORE.get_or_init(|| Arc::new(MemoryStore::new())).clone()
+}
+
+/// 单实例内存令牌桶
+pub struct MemoryStore {
+    state: Mutex<MemoryState>,
+}
+
+struct MemoryState {
+    buckets: HashMap<String, Bucket>,
+    last_cleanup: Instant,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// 令牌桶回满所需时间，超过后可回收
+    idle: Duration,
+}
+
+impl MemoryStore {
+    /// 回收空闲令牌桶的间隔
+    const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(MemoryState {
+                buckets: HashMap::new(),
+                last_cleanup: Instant::now(),
+            }),
+        }
+    }
+
+    fn acquire_at(&self, key: &str, quota: Quota, now: Instant) -> RateLimitDecision {
+        let capacity = f64::from(quota.limit);
+        let rate = capacity / quota.period.as_secs_f64();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if now.duration_since(state.last_cleanup) >= Self::CLEANUP_INTERVAL {
+            state
+                .buckets
+                .retain(|_, bucket| now.duration_since(bucket.updated) < bucket.idle);
+            state.last_cleanup = now;
+        }
+        let bucket = state
+            .buckets
+            .entry(key.to_string())
+            .or_insert_with(|| Bucket {
+                tokens: capacity,
+                updated: now,
+                idle: quota.period,
+            });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated = now;
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+        RateLimitDecision {
+            allowed,
+            remaining: bucket.tokens.floor() as u32,
+            reset: Duration::from_secs_f64((capacity - bucket.tokens) / rate),
+            retry_after: (!allowed).then(|| Duration::from_secs_f64((1.0 - bucket.tokens) / rate)),
+        }
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[salvo::async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn acquire(&self, key: &str, quota: Quota) -> anyhow::Result<RateLimitDecision> {
+        Ok(self.acquire_at(key, quota, Instant::now()))
+    }
+}
+
+/// 限流中间件：按第一条匹配的规则对客户端 IP、用户或租户限流，超出配额返回 429，
+/// 并在响应头中返回 `RateLimit-*` / `Retry-After`。需加在 Service 上，CORS 之后。
+pub struct RateLimiter {
+    rules: Vec<(Glob<'static>, &'static RateLimitRule)>,
+    store: Arc<dyn RateLimitStore>,
+    trusted_proxies: TrustedProxies,
+}
+
+impl RateLimiter {
+    /// 按 IP 限流时，仅来自 `trusted_proxies` 的请求按转发请求头识别客户端 IP
+    pub fn new(
+        config: &'static RateLimitConfig,
+        trusted_proxies: TrustedProxies,
+    ) -> anyhow::Result<Self> {
+        let rules = config
+            .rules()
+            .iter()
+            .map(|rule| {
+                anyhow::ensure!(
+                    rule.limit() > 0,
+                    "rate limit of `{}` must be positive",
+                    rule.path()
+                );
+                Ok((Glob::new(rule.path())?, rule))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            rules,
+            store: store(),
+            trusted_proxies,
+        })
+    }
+
+    fn rule(&self, path: &str) -> Option<(usize, &'static RateLimitRule)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .find(|(_, (glob, _))| glob.is_match(path))
+            .map(|(index, (_, rule))| (index, *rule))
+    }
+}
+
+#[handler]
+impl RateLimiter {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let Some((index, rule)) = self.rule(req.uri().path()) else {
+            return;
+        };
+        let quota = Quota {
+            limit: rule.limit(),
+            period: rule.period(),
+        };
+        // 不同规则各自计数
+        let key = format!(
+            "{index}:{}",
+            subject(req, rule.key(), &self.trusted_proxies)
+        );
+        let decision = match self.store.acquire(&key, quota).await {
+            Ok(decision) => decision,
+            Err(e) => {
+                // 存储不可用时放行，避免限流故障导致服务不可用
+                tracing::warn!("Rate limit store failed: {:#}", e);
+                return;
+            }
+        };
+        let headers = res.headers_mut();
+        headers.insert(RATE_LIMIT_LIMIT, HeaderValue::from(quota.limit));
+        headers.insert(RATE_LIMIT_REMAINING, HeaderValue::from(decision.remaining));
+        headers.insert(
+            RATE_LIMIT_RESET,
+            HeaderValue::from(ceil_secs(decision.reset)),
+        );
+        if let Some(retry_after) = decision.retry_after {
+            headers.insert(RETRY_AFTER, HeaderValue::from(ceil_secs(retry_after)));
+        }
+        if !decision.allowed {
+            write_error_response(res, ApiError::TooManyRequests);
+            ctrl.skip_rest();
+            return;
+        }
+        ctrl.call_next(req, depot, res).await;
+    }
+}
+
+/// 限流对象标识，用户、租户只取自校验通过的令牌，未登录时退回客户端 IP；
+/// 不使用客户端可任意设置的 `tenant-id` 请求头，避免轮换取值绕过限流或耗尽其他租户的配额
+fn subject(req: &Request, key: RateLimitKey, trusted_proxies: &TrustedProxies) -> String {
+    let ip = || format!("ip:{}", client_ip(req, trusted_proxies));
+    match key {
+        RateLimitKey::Ip => ip(),
+        RateLimitKey::User => principal(req).map_or_else(ip, |p| format!("user:{}", p.id)),
+        RateLimitKey::Tenant => {
+            principal(req).map_or_else(ip, |p| format!("tenant:{}", p.tenant_id))
+        }
+    }
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::jwt::{Principal, default_jwt};
+    use salvo::test::{ResponseExt, TestClient};
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    #[test]
+    fn test_token_bucket_refill() {
+        let store = MemoryStore::new();
+        let quota = Quota {
+            limit: 2,
+            period: Duration::from_secs(10),
+        };
+        let now = Instant::now();
+        assert!(store.acquire_at("k", quota, now).allowed);
+        assert!(store.acquire_at("k", quota, now).allowed);
+        let denied = store.acquire_at("k", quota, now);
+        assert!(!denied.allowed);
+        assert_eq!(denied.retry_after, Some(Duration::from_secs(5)));
+        assert!(store.acquire_at("other", quota, now).allowed);
+
+        let later = now + Duration::from_secs(5);
+        let decision = store.acquire_at("k", quota, later);
+        assert!(decision.allowed);
+        assert_eq!(decision.remaining, 0);
+        assert_eq!(decision.reset, Duration::from_secs(10));
+    }
+
+    /// 以 `x-test-peer` 请求头模拟连接的对端地址
+    #[handler]
+    async fn mock_peer(req: &mut Request) {
+        if let Some(peer) = req
+            .headers()
+            .get("x-test-peer")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<std::net::SocketAddr>().ok())
+        {
+            *req.remote_addr_mut() = peer.into();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_by_ip() {
+        let config: RateLimitConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "rules": [{ "path": "/login", "limit": 2, "period": 60 }],
+        }))
+        .unwrap();
+        let proxies = TrustedProxies::new(&["10.0.0.0/8".to_string()]).unwrap();
+        let limiter = RateLimiter::new(Box::leak(Box::new(config)), proxies).unwrap();
+        let router = Router::new()
+            .push(Router::with_path("login").post(hello))
+            .push(Router::with_path("open").get(hello));
+        let service = Service::new(router).hoop(mock_peer).hoop(limiter);
+
+        // 经可信代理转发，按 X-Forwarded-For 识别客户端
+        let login = |ip: &'static str| {
+            TestClient::post("http://127.0.0.1:8080/login")
+                .add_header("x-test-peer", "10.0.0.1:50000", true)
+                .add_header("x-forwarded-for", ip, true)
+        };
+        let res = login("192.168.0.1").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get("ratelimit-limit").unwrap(), "2");
+        assert_eq!(res.headers().get("ratelimit-remaining").unwrap(), "1");
+        login("192.168.0.1").send(&service).await;
+
+        let mut res = login("192.168.0.1").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(res.headers().get("retry-after").unwrap(), "30");
+        assert_eq!(res.headers().get("ratelimit-remaining").unwrap(), "0");
+        assert!(res.take_string().await.unwrap().contains("请求过于频繁"));
+
+        let res = login("192.168.0.2").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        // 客户端在左侧伪造的地址不影响识别结果
+        let res = login("192.168.0.2, 192.168.0.1").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::TOO_MANY_REQUESTS));
+        let res = TestClient::get("http://127.0.0.1:8080/open")
+            .send(&service)
+            .await;
+        assert!(!res.headers().contains_key("ratelimit-limit"));
+    }
+
+    #[tokio::test]
+    async fn test_ignore_forwarded_from_untrusted_peer() {
+        let config: RateLimitConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "rules": [{ "path": "/login", "limit": 1, "period": 60 }],
+        }))
+        .unwrap();
+        let proxies = TrustedProxies::new(&["10.0.0.0/8".to_string()]).unwrap();
+        let limiter = RateLimiter::new(Box::leak(Box::new(config)), proxies).unwrap();
+        let service = Service::new(Router::with_path("login").post(hello))
+            .hoop(mock_peer)
+            .hoop(limiter);
+
+        let login = |forwarded: &'static str| {
+            TestClient::post("http://127.0.0.1:8080/login")
+                .add_header("x-test-peer", "203.0.113.7:50000", true)
+                .add_header("x-forwarded-for", forwarded, true)
+                .add_header("x-real-ip", forwarded, true)
+        };
+        let res = login("192.168.0.1").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        // 直连的客户端每次更换转发请求头仍按对端地址计数
+        let res = login("192.168.0.2").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::TOO_MANY_REQUESTS));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_by_tenant() {
+        let config: RateLimitConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "rules": [{ "path": "/orders", "key": "tenant", "limit": 1, "period": 60 }],
+        }))
+        .unwrap();
+        let limiter =
+            RateLimiter::new(Box::leak(Box::new(config)), TrustedProxies::default()).unwrap();
+        let service = Service::new(Router::with_path("orders").get(hello)).hoop(limiter);
+        let orders = || TestClient::get("http://127.0.0.1:8080/orders");
+
+        // 未登录时按 IP 计数，轮换 tenant-id 请求头无效
+        let res = orders()
+            .add_header("tenant-id", "1", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        let res = orders()
+            .add_header("tenant-id", "2", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::TOO_MANY_REQUESTS));
+
+        let token = default_jwt()
+            .encode(Principal {
+                tenant_id: 1,
+                id: 1,
+                name: String::from("admin"),
+            })
+            .unwrap();
+        let res = orders().bearer_auth(&token).send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        let res = orders().bearer_auth(&token).send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::TOO_MANY_REQUESTS));
+    }
+}
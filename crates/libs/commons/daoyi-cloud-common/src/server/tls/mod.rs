@@ -0,0 +1,73 @@
+use crate::conf::server::TlsConfig;
+use anyhow::Context;
+use salvo::conn::rustls::{Keycert, RustlsConfig, ServerConfig};
+use tokio::sync::watch;
+use tokio_stream::wrappers::WatchStream;
+
+/// 可热加载的 rustls 配置，每个监听器通过 [`TlsReloader::stream`] 订阅最新证书
+pub struct TlsReloader {
+    receiver: watch::Receiver<RustlsConfig>,
+}
+
+impl TlsReloader {
+    pub fn new(config: &'static TlsConfig, http2: bool) -> anyhow::Result<Self> {
+        let (cert, key) = read_keycert(config)?;
+        let initial = build_config(cert.clone(), key.clone(), http2)?;
+        let (sender, receiver) = watch::channel(initial);
+        if let Some(interval) = config.reload_interval() {
+            tokio::spawn(async move {
+                let mut current = (cert, key);
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let loaded = match read_keycert(config) {
+                        Ok(loaded) if loaded != current => loaded,
+                        Ok(_) => continue,
+                        Err(e) => {
+                            tracing::warn!("Failed to read TLS certificate: {e:#}");
+                            continue;
+                        }
+                    };
+                    match build_config(loaded.0.clone(), loaded.1.clone(), http2) {
+                        Ok(rustls_config) => {
+                            tracing::info!("TLS certificate reloaded");
+                            current = loaded;
+                            if sender.send(rustls_config).is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => tracing::warn!("Invalid TLS certificate, keep the old one: {e:#}"),
+                    }
+                }
+            });
+        }
+        Ok(Self { receiver })
+    }
+
+    pub fn stream(&self) -> WatchStream<RustlsConfig> {
+        WatchStream::new(self.receiver.clone())
+    }
+}
+
+fn read_keycert(config: &TlsConfig) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+    let cert = std::fs::read(config.cert())
+        .with_context(|| format!("Failed to read TLS certificate: {}", config.cert()))?;
+    let key = std::fs::read(config.key())
+        .with_context(|| format!("Failed to read TLS key: {}", config.key()))?;
+    Ok((cert, key))
+}
+
+fn build_config(cert: Vec<u8>, key: Vec<u8>, http2: bool) -> anyhow::Result<RustlsConfig> {
+    let alpn_protocols = if http2 {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    } else {
+        vec![b"http/1.1".to_vec()]
+    };
+    let config =
+        RustlsConfig::new(Keycert::new().cert(cert).key(key)).alpn_protocols(alpn_protocols);
+    // 提前校验证书与私钥，避免在第一次握手时才暴露错误
+    let _: ServerConfig = config
+        .clone()
+        .try_into()
+        .context("Invalid TLS certificate or key")?;
+    Ok(config)
+}
@@ -0,0 +1,117 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+type HookFuture = Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>;
+type Hook = Box<dyn FnOnce() -> HookFuture + Send>;
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static HOOKS: Mutex<Vec<(String, Hook)>> = Mutex::new(Vec::new());
+
+/// 注册停机钩子，服务停止接收请求且处理完在途请求后按注册的逆序执行
+pub fn register<F, Fut>(name: impl Into<String>, hook: F)
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    let hook: Hook = Box::new(move || Box::pin(hook()));
+    HOOKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push((name.into(), hook));
+}
+
+/// 是否已进入停机流程
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::Acquire)
+}
+
+/// 标记进入停机流程
+pub fn begin() {
+    SHUTTING_DOWN.store(true, Ordering::Release);
+}
+
+/// 等待 SIGINT / SIGTERM，返回收到的信号名称
+pub async fn wait_signal() -> &'static str {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => "SIGINT",
+        _ = terminate => "SIGTERM",
+    }
+}
+
+/// 按注册的逆序执行全部停机钩子，单个钩子失败或超时不影响其余钩子，任一失败时返回错误
+pub async fn run_hooks(timeout: Duration) -> anyhow::Result<()> {
+    let hooks = std::mem::take(&mut *HOOKS.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut failed = Vec::new();
+    for (name, hook) in hooks.into_iter().rev() {
+        tracing::info!("Running shutdown hook: {}", name);
+        match tokio::time::timeout(timeout, hook()).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                tracing::error!("Shutdown hook `{}` failed: {:#}", name, e);
+                failed.push(name);
+            }
+            Err(_) => {
+                tracing::error!("Shutdown hook `{}` timed out after {:?}", name, timeout);
+                failed.push(name);
+            }
+        }
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("Shutdown hooks failed: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_run_hooks() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        for name in ["first", "second"] {
+            let order = order.clone();
+            register(name, move || async move {
+                order.lock().unwrap().push(name);
+                Ok(())
+            });
+        }
+        register("failing", || async { anyhow::bail!("boom") });
+        register("slow", || async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(())
+        });
+
+        let err = run_hooks(Duration::from_millis(50)).await.unwrap_err();
+        assert_eq!(err.to_string(), "Shutdown hooks failed: slow, failing");
+        assert_eq!(*order.lock().unwrap(), ["second", "first"]);
+        // 钩子只执行一次
+        assert!(run_hooks(Duration::from_millis(50)).await.is_ok());
+    }
+}
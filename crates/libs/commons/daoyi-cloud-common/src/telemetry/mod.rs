@@ -0,0 +1,212 @@
+//! OpenTelemetry 链路追踪，需启用 `otel` cargo feature 并配置 `telemetry.enabled: true`。
+//! 每个请求创建一个 server span，沿用上游 `traceparent`，数据库查询生成子 span，经 OTLP/HTTP 导出。
+
+use salvo::http::HeaderMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::Span;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// 链路追踪是否已启用
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Acquire)
+}
+
+/// 以请求头中的 `traceparent` 作为 span 的父上下文
+pub fn set_parent(span: &Span, headers: &HeaderMap) {
+    #[cfg(feature = "otel")]
+    if enabled() {
+        otel::set_parent(span, headers);
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = (span, headers);
+}
+
+/// 将当前 span 的上下文写入请求头，供调用其他服务时传递链路
+pub fn inject(headers: &mut HeaderMap) {
+    #[cfg(feature = "otel")]
+    if enabled() {
+        otel::inject(&Span::current(), headers);
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = headers;
+}
+
+#[cfg(feature = "otel")]
+pub use otel::layer;
+
+#[cfg(feature = "otel")]
+mod otel {
+    use super::ENABLED;
+    use crate::conf::TelemetryConfig;
+    use anyhow::Context;
+    use opentelemetry::propagation::{Extractor, Injector, TextMapPropagator};
+    use opentelemetry::trace::{TraceContextExt, TracerProvider};
+    use opentelemetry::{Context as OtelContext, global};
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::Resource;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::{Sampler, SdkTracer, SdkTracerProvider};
+    use salvo::http::{HeaderMap, HeaderName, HeaderValue};
+    use std::sync::atomic::Ordering;
+    use tracing::Span;
+    use tracing::subscriber::Subscriber;
+    use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+    use tracing_subscriber::registry::LookupSpan;
+
+    /// 按配置创建 OTLP 导出的 tracing 层，未启用时返回 `None`
+    pub fn layer<S>(
+        config: &TelemetryConfig,
+        app_name: &str,
+    ) -> anyhow::Result<Option<OpenTelemetryLayer<S, SdkTracer>>>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        if !config.enabled() {
+            return Ok(None);
+        }
+        let provider = provider(config, app_name)?;
+        let tracer = provider.tracer("daoyi-cloud");
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        global::set_tracer_provider(provider.clone());
+        crate::shutdown::register("telemetry", move || async move {
+            // 导出器使用阻塞 HTTP 客户端，需在阻塞线程中关闭
+            tokio::task::spawn_blocking(move || provider.shutdown())
+                .await?
+                .with_context(|| "Failed to shutdown tracer provider")
+        });
+        ENABLED.store(true, Ordering::Release);
+        Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+    }
+
+    pub(super) fn provider(
+        config: &TelemetryConfig,
+        app_name: &str,
+    ) -> anyhow::Result<SdkTracerProvider> {
+        let endpoint = config.endpoint().to_string();
+        let timeout = config.timeout();
+        // 阻塞 HTTP 客户端不能在异步上下文中创建
+        let exporter = std::thread::spawn(move || {
+            SpanExporter::builder()
+                .with_http()
+                .with_endpoint(endpoint)
+                .with_timeout(timeout)
+                .build()
+        })
+        .join()
+        .map_err(|_| anyhow::anyhow!("OTLP exporter builder panicked"))?
+        .with_context(|| "Failed to build OTLP exporter")?;
+        let service_name = config.service_name().unwrap_or(app_name).to_string();
+        Ok(SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                config.sample_ratio(),
+            ))))
+            .with_resource(Resource::builder().with_service_name(service_name).build())
+            .build())
+    }
+
+    pub(super) fn set_parent(span: &Span, headers: &HeaderMap) {
+        let cx = TraceContextPropagator::new().extract(&HeaderExtractor(headers));
+        if cx.span().span_context().is_valid() {
+            let _ = span.set_parent(cx);
+        }
+    }
+
+    pub(super) fn inject(span: &Span, headers: &mut HeaderMap) {
+        let cx: OtelContext = span.context();
+        TraceContextPropagator::new().inject_context(&cx, &mut HeaderInjector(headers));
+    }
+
+    struct HeaderExtractor<'a>(&'a HeaderMap);
+
+    impl Extractor for HeaderExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0.keys().map(HeaderName::as_str).collect()
+        }
+    }
+
+    struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+    impl Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use salvo::conn::Acceptor;
+        use salvo::prelude::*;
+        use std::sync::Mutex;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        static RECEIVED: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+        /// 模拟 OTLP 采集端
+        #[handler]
+        async fn collect(req: &mut Request) -> StatusCode {
+            let body = req.payload().await.unwrap().to_vec();
+            RECEIVED.lock().unwrap().extend(body);
+            StatusCode::OK
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn test_export_with_remote_parent() {
+            let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+            let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+            let router = Router::with_path("v1/traces").post(collect);
+            tokio::spawn(Server::new(acceptor).serve(router));
+
+            let config: TelemetryConfig = serde_json::from_value(serde_json::json!({
+                "enabled": true,
+                "endpoint": format!("http://{addr}/v1/traces"),
+            }))
+            .unwrap();
+            let provider = provider(&config, "test").unwrap();
+            let subscriber = tracing_subscriber::registry().with(
+                tracing_opentelemetry::layer().with_tracer(provider.tracer("test")),
+            );
+
+            let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                "traceparent",
+                HeaderValue::from_str(&format!("00-{trace_id}-00f067aa0ba902b7-01")).unwrap(),
+            );
+            let mut outgoing = HeaderMap::new();
+            tracing::subscriber::with_default(subscriber, || {
+                let span = tracing::info_span!("request", otel.kind = "server");
+                set_parent(&span, &headers);
+                inject(&span, &mut outgoing);
+            });
+            let traceparent = outgoing.get("traceparent").unwrap().to_str().unwrap();
+            assert!(traceparent.starts_with(&format!("00-{trace_id}-")));
+
+            tokio::task::spawn_blocking(move || provider.shutdown())
+                .await
+                .unwrap()
+                .unwrap();
+            let received = RECEIVED.lock().unwrap();
+            let trace_id_bytes: Vec<u8> = (0..trace_id.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&trace_id[i..i + 2], 16).unwrap())
+                .collect();
+            assert!(
+                received
+                    .windows(trace_id_bytes.len())
+                    .any(|w| w == trace_id_bytes.as_slice())
+            );
+        }
+    }
+}
@@ -0,0 +1,371 @@
+//! daoyi-cloud-rs 过程宏
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use std::collections::BTreeMap;
+use syn::meta::ParseNestedMeta;
+use syn::{
+    Data, DeriveInput, Expr, ExprLit, Fields, GenericArgument, Lit, LitStr, PathArguments, Token,
+    Type, parse_macro_input, token,
+};
+
+/// 将字段上 `validator` 的校验规则写入 `ToSchema` 生成的 OpenAPI schema：
+///
+/// - `length` → `minLength` / `maxLength`，集合类型为 `minItems` / `maxItems`
+/// - `range` → `minimum` / `maximum` / `exclusiveMinimum` / `exclusiveMaximum`
+/// - `email` / `url` → `format`
+/// - `regex(path = ...)` → `pattern`
+/// - `custom(function = ...)` → 通过 `openapi::register_custom_rule` 为该函数登记的约束
+/// - `required` → `required`
+/// - `nested` → 同时处理字段类型的规则
+///
+/// 字段名按 `#[serde(rename_all)]` / `#[serde(rename)]` 转换，同时生成 `field_names`，
+/// 校验错误据此使用 JSON 字段名。
+#[proc_macro_derive(ValidateSchema)]
+pub fn derive_validate_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "ValidateSchema only supports structs with named fields",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "ValidateSchema only supports structs with named fields",
+        ));
+    };
+    let rename_all = serde_rename_all(&input)?;
+
+    let mut rules = Vec::new();
+    let mut nested = Vec::new();
+    let mut names = Vec::new();
+    for field in &fields.named {
+        let serde = SerdeField::parse(field)?;
+        if serde.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field").to_string();
+        let ident = ident.trim_start_matches("r#");
+        let name = serde
+            .rename
+            .unwrap_or_else(|| rename(ident, rename_all.as_deref()));
+        let mut rule = FieldRule::default();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("validate")) {
+            attr.parse_nested_meta(|meta| rule.parse(meta, &field.ty))?;
+        }
+        let ty = inner_type(&field.ty);
+        let nested_names = if rule.nested {
+            nested.push(quote! {
+                <#ty as ::daoyi_cloud_common::openapi::ValidateSchema>::register(components);
+            });
+            quote! {
+                ::std::option::Option::Some(
+                    <#ty as ::daoyi_cloud_common::openapi::ValidateSchema>::field_names
+                )
+            }
+        } else {
+            quote! { ::std::option::Option::None }
+        };
+        // flatten 字段的错误归入所在对象
+        let serialized = if serde.flatten { "" } else { name.as_str() };
+        names.push(quote! {
+            ::daoyi_cloud_common::openapi::FieldName {
+                field: #ident,
+                name: #serialized,
+                nested: #nested_names,
+            }
+        });
+        if !serde.flatten && (!rule.values.is_empty() || rule.required) {
+            let values = rule.values.iter().map(|(key, value)| {
+                let key = syn::Ident::new(key, proc_macro2::Span::call_site());
+                quote! { #key: #value, }
+            });
+            let required = rule.required;
+            rules.push(quote! {
+                ::daoyi_cloud_common::openapi::FieldRule {
+                    field: #name,
+                    required: #required,
+                    #(#values)*
+                    ..::std::default::Default::default()
+                }
+            });
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::daoyi_cloud_common::openapi::ValidateSchema for #ident #ty_generics #where_clause {
+            fn field_rules() -> ::std::vec::Vec<::daoyi_cloud_common::openapi::FieldRule> {
+                ::std::vec![#(#rules),*]
+            }
+
+            fn register_nested(components: &mut ::salvo::oapi::Components) {
+                #(#nested)*
+            }
+
+            fn field_names() -> ::std::vec::Vec<::daoyi_cloud_common::openapi::FieldName> {
+                ::std::vec![#(#names),*]
+            }
+        }
+    })
+}
+
+/// 单个字段的规则，`values` 为 `FieldRule` 字段名到取值表达式
+#[derive(Default)]
+struct FieldRule {
+    values: BTreeMap<&'static str, TokenStream2>,
+    required: bool,
+    nested: bool,
+}
+
+impl FieldRule {
+    fn parse(&mut self, meta: ParseNestedMeta, ty: &Type) -> syn::Result<()> {
+        let Some(name) = meta.path.get_ident().map(ToString::to_string) else {
+            return skip(meta);
+        };
+        match name.as_str() {
+            "length" => {
+                let (min, max) = if is_collection(ty) {
+                    ("min_items", "max_items")
+                } else {
+                    ("min_length", "max_length")
+                };
+                meta.parse_nested_meta(|arg| {
+                    let key = match arg.path.get_ident().map(ToString::to_string).as_deref() {
+                        Some("min") => vec![min],
+                        Some("max") => vec![max],
+                        Some("equal") => vec![min, max],
+                        _ => return skip(arg),
+                    };
+                    let value: Expr = arg.value()?.parse()?;
+                    for key in key {
+                        self.values
+                            .insert(key, quote! { ::std::option::Option::Some((#value) as usize) });
+                    }
+                    Ok(())
+                })
+            }
+            "range" => meta.parse_nested_meta(|arg| {
+                let key = match arg.path.get_ident().map(ToString::to_string).as_deref() {
+                    Some("min") => "minimum",
+                    Some("max") => "maximum",
+                    Some("exclusive_min") => "exclusive_minimum",
+                    Some("exclusive_max") => "exclusive_maximum",
+                    _ => return skip(arg),
+                };
+                let value: Expr = arg.value()?.parse()?;
+                self.values
+                    .insert(key, quote! { ::std::option::Option::Some((#value) as f64) });
+                Ok(())
+            }),
+            "email" | "url" => {
+                let format = if name == "email" { "email" } else { "uri" };
+                self.values
+                    .insert("format", quote! { ::std::option::Option::Some(#format) });
+                skip(meta)
+            }
+            "regex" => meta.parse_nested_meta(|arg| {
+                if !arg.path.is_ident("path") {
+                    return skip(arg);
+                }
+                let path = match arg.value()?.parse::<Expr>()? {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(path),
+                        ..
+                    }) => path.parse::<Expr>()?,
+                    path => path,
+                };
+                self.values.entry("pattern").or_insert(quote! {
+                    ::std::option::Option::Some(::std::string::ToString::to_string((#path).as_str()))
+                });
+                Ok(())
+            }),
+            "custom" => meta.parse_nested_meta(|arg| {
+                if !arg.path.is_ident("function") {
+                    return skip(arg);
+                }
+                let function = match arg.value()?.parse::<Expr>()? {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(function),
+                        ..
+                    }) => function.value(),
+                    Expr::Path(function) => function
+                        .path
+                        .segments
+                        .iter()
+                        .map(|segment| segment.ident.to_string())
+                        .collect::<Vec<_>>()
+                        .join("::"),
+                    function => {
+                        return Err(syn::Error::new_spanned(function, "expected function path"));
+                    }
+                };
+                let function = function.rsplit("::").next().unwrap_or_default().to_string();
+                self.values
+                    .insert("custom", quote! { ::std::option::Option::Some(#function) });
+                Ok(())
+            }),
+            "required" => {
+                self.required = true;
+                skip(meta)
+            }
+            "nested" => {
+                self.nested = true;
+                skip(meta)
+            }
+            _ => skip(meta),
+        }
+    }
+}
+
+/// 跳过不关心的规则及其参数
+fn skip(meta: ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(token::Paren) {
+        meta.parse_nested_meta(skip)
+    } else if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>().map(|_| ())
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct SerdeField {
+    rename: Option<String>,
+    skip: bool,
+    flatten: bool,
+}
+
+impl SerdeField {
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut serde = Self::default();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    if meta.input.peek(Token![=]) {
+                        serde.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                        return Ok(());
+                    }
+                    // rename(deserialize = "...")
+                    return meta.parse_nested_meta(|arg| {
+                        if arg.path.is_ident("deserialize") {
+                            serde.rename = Some(arg.value()?.parse::<LitStr>()?.value());
+                            Ok(())
+                        } else {
+                            skip(arg)
+                        }
+                    });
+                }
+                if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                    serde.skip = true;
+                } else if meta.path.is_ident("flatten") {
+                    serde.flatten = true;
+                }
+                skip(meta)
+            })?;
+        }
+        Ok(serde)
+    }
+}
+
+fn serde_rename_all(input: &DeriveInput) -> syn::Result<Option<String>> {
+    let mut rename_all = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                if meta.input.peek(Token![=]) {
+                    rename_all = Some(meta.value()?.parse::<LitStr>()?.value());
+                    return Ok(());
+                }
+                return meta.parse_nested_meta(|arg| {
+                    if arg.path.is_ident("deserialize") {
+                        rename_all = Some(arg.value()?.parse::<LitStr>()?.value());
+                        Ok(())
+                    } else {
+                        skip(arg)
+                    }
+                });
+            }
+            skip(meta)
+        })?;
+    }
+    Ok(rename_all)
+}
+
+/// 按 serde 的 `rename_all` 规则转换 snake_case 字段名
+fn rename(field: &str, rule: Option<&str>) -> String {
+    let words = field.split('_').filter(|word| !word.is_empty());
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    };
+    match rule {
+        Some("camelCase") => words
+            .enumerate()
+            .map(|(i, word)| {
+                if i == 0 {
+                    word.to_string()
+                } else {
+                    capitalize(word)
+                }
+            })
+            .collect(),
+        Some("PascalCase") => words.map(capitalize).collect(),
+        Some("lowercase") => field.to_lowercase(),
+        Some("UPPERCASE") => field.to_uppercase(),
+        Some("SCREAMING_SNAKE_CASE") => field.to_uppercase(),
+        Some("kebab-case") => field.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => field.replace('_', "-").to_uppercase(),
+        _ => field.to_string(),
+    }
+}
+
+/// 去掉 `Option` 及集合的外层，得到元素类型
+fn inner_type(ty: &Type) -> &Type {
+    if let Type::Path(path) = ty
+        && let Some(segment) = path.path.segments.last()
+        && matches!(
+            segment.ident.to_string().as_str(),
+            "Option" | "Vec" | "VecDeque" | "HashSet" | "BTreeSet" | "IndexSet" | "Box"
+        )
+        && let PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(GenericArgument::Type(inner)) = args.args.first()
+    {
+        return inner_type(inner);
+    }
+    ty
+}
+
+/// 是否为集合类型（可为 `Option` 包裹），`length` 对应 `minItems` / `maxItems`
+fn is_collection(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+    match segment.ident.to_string().as_str() {
+        "Vec" | "VecDeque" | "HashSet" | "BTreeSet" | "IndexSet" => true,
+        "Option" => match &segment.arguments {
+            PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(GenericArgument::Type(inner)) => is_collection(inner),
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
+    }
+}
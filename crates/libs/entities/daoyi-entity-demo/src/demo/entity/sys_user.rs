@@ -1,23 +1,30 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 2.0
 
 use daoyi_cloud_common::constants::enumeration::Gender;
+use daoyi_cloud_common::db::constraint::{Constraint, EntityConstraints};
+use daoyi_cloud_common::openapi::ValidateSchema;
+use daoyi_cloud_common::utils::id_utils;
 use salvo::oapi::ToSchema;
+use sea_orm::ActiveValue::Set;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
 /// 系统用户
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize, ToSchema)]
+#[derive(
+    Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize, ToSchema, ValidateSchema,
+)]
 #[sea_orm(table_name = "sys_user")]
 #[serde(rename_all = "camelCase")]
 pub struct Model {
     /// 用户ID
-    #[sea_orm(primary_key)]
+    #[sea_orm(primary_key, auto_increment = false)]
     pub id: i64,
     /// 用户姓名
     pub name: String,
     /// 性别
     pub gender: Gender,
     /// 登录账号
+    #[sea_orm(unique)]
     pub account: String,
     /// 登录密码
     pub password: String,
@@ -36,4 +43,32 @@ pub struct Model {
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {}
 
-impl ActiveModelBehavior for ActiveModel {}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    /// 主键与时间戳由应用生成，不依赖数据库自增和默认值，以兼容各数据库后端
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let now = chrono::Local::now().naive_local();
+        if insert {
+            if self.id.is_not_set() {
+                self.id = Set(id_utils::next_id());
+            }
+            if self.created_at.is_not_set() {
+                self.created_at = Set(now);
+            }
+        }
+        self.updated_at = Set(now);
+        Ok(self)
+    }
+}
+
+impl EntityConstraints for Entity {
+    fn constraints() -> Vec<Constraint> {
+        vec![
+            Constraint::new("uk_sys_user_account", Column::Account, "account")
+                .message("{demo.account_taken}"),
+        ]
+    }
+}
@@ -1,3 +1,12 @@
 pub mod entity;
 pub mod models;
 pub mod service;
+
+daoyi_cloud_common::error_codes! {
+    /// demo 模块错误码（10000–10999）
+    pub mod error_code("demo", 10_000..=10_999) {
+        ACCOUNT_OR_PASSWORD_INVALID = 10_001 => "账号或密码错误";
+        ACCOUNT_DISABLED = 10_002 => "账号已被禁用";
+        USER_NOT_FOUND = 10_003 => "用户不存在";
+    }
+}
@@ -1,16 +1,17 @@
+use daoyi_cloud_common::openapi::ValidateSchema;
 use salvo::oapi::ToSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 /// 登录请求参数
-#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[derive(Debug, Deserialize, Validate, ToSchema, ValidateSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginParams {
     /// 登录账号
-    #[validate(length(min = 1, max = 16, message = "账号长度必须在 1 到 16 之间"))]
+    #[validate(length(min = 1, max = 16, message = "{demo.account_length}"))]
     pub account: String,
     /// 登录密码
-    #[validate(length(min = 6, max = 16, message = "密码长度必须在 6 到 16 之间"))]
+    #[validate(length(min = 6, max = 16, message = "{demo.password_length}"))]
     pub password: String,
 }
 
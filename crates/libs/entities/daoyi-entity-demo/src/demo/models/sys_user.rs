@@ -1,16 +1,19 @@
+use crate::demo::entity::sys_user;
 use crate::demo::entity::sys_user::ActiveModel;
 use daoyi_cloud_common::constants::default_values::default_true;
 use daoyi_cloud_common::constants::enumeration::Gender;
-use daoyi_cloud_common::extract::validations::validate_mobile_phone;
+use daoyi_cloud_common::db::validate::{DbRule, ValidateDb};
+use daoyi_cloud_common::extract::validations::{validate_mobile_phone, validate_not_future};
+use daoyi_cloud_common::openapi::ValidateSchema;
 use daoyi_cloud_common::pojo::pagination::PageParam;
-use salvo::oapi::{ToParameters, ToSchema};
+use salvo::oapi::ToSchema;
 use sea_orm::DeriveIntoActiveModel;
 use sea_orm::prelude::Date;
 use serde::Deserialize;
 use validator::Validate;
 
 /// 用户查询参数
-#[derive(Debug, Deserialize, Validate, ToParameters, ToSchema)]
+#[derive(Debug, Deserialize, Validate, ToSchema, ValidateSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserQueryParams {
     /// 搜索关键词
@@ -21,26 +24,36 @@ pub struct UserQueryParams {
 }
 
 /// 用户新增/编辑参数
-#[derive(Debug, Deserialize, Validate, DeriveIntoActiveModel, ToSchema)]
+#[derive(Debug, Deserialize, Validate, DeriveIntoActiveModel, ToSchema, ValidateSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserParams {
     /// 用户姓名
-    #[validate(length(min = 1, max = 16, message = "用户姓名长度必须在 1 到 16 之间"))]
+    #[validate(length(min = 1, max = 16, message = "{demo.name_length}"))]
     pub name: String,
     /// 性别
     pub gender: Gender,
     /// 登录账号
-    #[validate(length(min = 1, max = 16, message = "账号长度必须在 1 到 16 之间"))]
+    #[validate(length(min = 1, max = 16, message = "{demo.account_length}"))]
     pub account: String,
     /// 登录密码
-    #[validate(length(min = 6, max = 16, message = "密码长度必须在 6 到 16 之间"))]
+    #[validate(length(min = 6, max = 16, message = "{demo.password_length}"))]
     pub password: String,
     /// 手机号码
     #[validate(custom(function = "validate_mobile_phone"))]
     pub mobile_phone: String,
     /// 出生日期
+    #[validate(custom(function = "validate_not_future"))]
     pub birthday: Date,
     /// 是否启用
     #[serde(default = "default_true")]
     pub enabled: bool,
 }
+
+impl ValidateDb for UserParams {
+    fn db_rules(&self) -> Vec<DbRule> {
+        vec![
+            DbRule::unique::<sys_user::Entity>("account", sys_user::Column::Account, &self.account)
+                .message("{demo.account_taken}"),
+        ]
+    }
+}
@@ -1,10 +1,11 @@
 use crate::demo::entity::prelude::*;
 use crate::demo::entity::sys_user;
+use crate::demo::error_code;
 use crate::demo::models::auth::{LoginParams, LoginResult};
 use daoyi_cloud_common::auth::jwt::{Principal, default_jwt};
 use daoyi_cloud_common::constants::global_values::ROOT_ID;
 use daoyi_cloud_common::db;
-use daoyi_cloud_common::error::{ApiError, ApiResult};
+use daoyi_cloud_common::error::ApiResult;
 use daoyi_cloud_common::utils::passwd_utils;
 use sea_orm::prelude::*;
 
@@ -13,12 +14,12 @@ pub async fn login(params: LoginParams) -> ApiResult<LoginResult> {
         .filter(sys_user::Column::Account.eq(params.account))
         .one(db::get())
         .await?
-        .ok_or_else(|| ApiError::Biz(String::from("账号或密码错误")))?;
+        .ok_or(error_code::ACCOUNT_OR_PASSWORD_INVALID)?;
     if !model.enabled {
-        return Err(ApiError::Biz(String::from("账号已被禁用")));
+        return Err(error_code::ACCOUNT_DISABLED.into());
     }
     if !passwd_utils::verify_passwd(&params.password, &model.password)? {
-        return Err(ApiError::Biz(String::from("账号或密码错误")));
+        return Err(error_code::ACCOUNT_OR_PASSWORD_INVALID.into());
     }
     let principal = Principal {
         tenant_id: ROOT_ID,
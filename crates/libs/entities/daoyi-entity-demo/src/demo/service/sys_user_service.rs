@@ -1,8 +1,10 @@
 use crate::demo::entity::prelude::*;
 use crate::demo::entity::sys_user;
+use crate::demo::error_code;
 use crate::demo::models::sys_user::{UserParams, UserQueryParams};
 use daoyi_cloud_common::db;
-use daoyi_cloud_common::error::{ApiError, ApiResult};
+use daoyi_cloud_common::db::validate::ValidateDb;
+use daoyi_cloud_common::error::ApiResult;
 use daoyi_cloud_common::pojo::pagination::PageResult;
 use daoyi_cloud_common::utils::passwd_utils::hash_passwd;
 use sea_orm::prelude::*;
@@ -45,6 +47,7 @@ pub async fn query_users() -> ApiResult<Vec<sys_user::Model>> {
 }
 
 pub async fn create_user(params: UserParams) -> ApiResult<sys_user::Model> {
+    params.validate_db(None).await?;
     let mut active_model = params.into_active_model();
     active_model.password = ActiveValue::Set(hash_passwd(&active_model.password.take().unwrap())?);
     let model = active_model.insert(db::get()).await?;
@@ -55,7 +58,8 @@ pub async fn update_user_by_id(id: i64, params: UserParams) -> ApiResult<bool> {
     let model = SysUser::find_by_id(id)
         .one(db::get())
         .await?
-        .ok_or_else(|| ApiError::Biz(String::from("用户不存在")))?;
+        .ok_or(error_code::USER_NOT_FOUND)?;
+    params.validate_db(Some(id)).await?;
     let password = params.password.clone();
     let mut active_model = params.into_active_model();
     active_model.id = ActiveValue::Unchanged(model.id);
@@ -78,3 +82,146 @@ pub async fn delete_user_by_id(id: i64) -> ApiResult<bool> {
     tracing::info!("delete_user_by_id {id} result: {:?}", result);
     Ok(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::models::auth::LoginParams;
+    use crate::demo::service::auth_service;
+    use daoyi_cloud_common::constants::enumeration::Gender;
+    use daoyi_cloud_common::db::constraint;
+    use daoyi_cloud_common::db::validate::{DbRule, validate_rules};
+    use daoyi_cloud_common::error::ApiError;
+    use daoyi_cloud_common::pojo::pagination::PageParam;
+    use daoyi_cloud_common::utils::id_utils;
+    use salvo::http::StatusCode;
+    use sea_orm::prelude::Date;
+
+    const DDL: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../../docs/db/demo/ddl.sql"
+    ));
+
+    fn user_params(account: &str, password: &str) -> UserParams {
+        UserParams {
+            name: String::from("王五"),
+            gender: Gender::Male,
+            account: String::from(account),
+            password: String::from(password),
+            mobile_phone: String::from("13912345678"),
+            birthday: Date::from_ymd_opt(2000, 1, 1).unwrap(),
+            enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_service_on_sqlite() {
+        let db_conf = serde_json::from_value(serde_json::json!({
+            "backend": "sqlite",
+            "database": ":memory:",
+        }))
+        .unwrap();
+        db::init_with(&db_conf).await.unwrap();
+        id_utils::init().unwrap();
+        db::get().execute_unprepared(DDL).await.unwrap();
+
+        let created = create_user(user_params("wangwu", "123456")).await.unwrap();
+        assert_ne!(created.password, "123456");
+        assert_eq!(created.created_at, created.updated_at);
+
+        // 账号唯一
+        let err = create_user(user_params("wangwu", "123456"))
+            .await
+            .unwrap_err();
+        let data = err.to_api_response().data.unwrap();
+        assert_eq!(data[0].field, "account");
+        assert_eq!(data[0].code, "unique");
+
+        // 绕过 validate_db 时由唯一索引兜底，转换为 409；按实体映射转换，不修改全局注册
+        let err = user_params("wangwu", "123456")
+            .into_active_model()
+            .insert(db::get())
+            .await
+            .unwrap_err();
+        let err = constraint::to_entity_error::<SysUser>(&err).unwrap();
+        assert_eq!(err.status_code(), StatusCode::CONFLICT);
+        let data = err.to_api_response().data.unwrap();
+        assert_eq!(data[0].field, "account");
+        assert_eq!(data[0].code, "unique");
+
+        let found = get_user_by_id(created.id).await.unwrap().unwrap();
+        assert_eq!(found.account, "wangwu");
+
+        let page = query_page(UserQueryParams {
+            keyword: Some(String::from("wang")),
+            pagination: PageParam {
+                page_no: 1,
+                page_size: 10,
+            },
+        })
+        .await
+        .unwrap();
+        assert_eq!(page.total, 1);
+
+        let login = auth_service::login(LoginParams {
+            account: String::from("wangwu"),
+            password: String::from("123456"),
+        })
+        .await
+        .unwrap();
+        assert!(!login.access_token.is_empty());
+
+        // 种子数据中的禁用账号
+        assert!(
+            auth_service::login(LoginParams {
+                account: String::from("admin"),
+                password: String::from("123456"),
+            })
+            .await
+            .is_err()
+        );
+
+        let mut params = user_params("wangwu", "");
+        params.name = String::from("王五五");
+        assert!(update_user_by_id(created.id, params).await.unwrap());
+        let updated = get_user_by_id(created.id).await.unwrap().unwrap();
+        assert_eq!(updated.name, "王五五");
+        assert_eq!(updated.password, created.password);
+
+        // 更新时排除当前记录，但不能使用其他用户的账号
+        let other = create_user(user_params("sunqi", "123456")).await.unwrap();
+        let err = update_user_by_id(other.id, user_params("wangwu", ""))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiError::Validation { .. }));
+
+        // 引用的记录须存在且已启用，种子数据中 admin 已禁用
+        let rule = |account: &str| {
+            DbRule::enabled::<SysUser>(
+                "account",
+                sys_user::Column::Account,
+                sys_user::Column::Enabled,
+                account,
+            )
+        };
+        let err = validate_rules(db::get(), vec![rule("admin"), rule("nobody")], None)
+            .await
+            .unwrap_err();
+        let codes: Vec<_> = err
+            .to_api_response()
+            .data
+            .unwrap()
+            .into_iter()
+            .map(|e| e.code)
+            .collect();
+        assert_eq!(codes, ["disabled", "exists"]);
+        assert!(
+            validate_rules(db::get(), vec![rule("wangwu")], None)
+                .await
+                .is_ok()
+        );
+
+        assert!(delete_user_by_id(created.id).await.unwrap());
+        assert!(get_user_by_id(created.id).await.unwrap().is_none());
+    }
+}
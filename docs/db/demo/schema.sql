@@ -6,7 +6,7 @@ drop table if exists sys_user;
 
 CREATE TABLE IF NOT EXISTS sys_user
 (
-    id           BIGINT                             NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    id           BIGINT                             NOT NULL PRIMARY KEY,
     name         VARCHAR(16)                        NOT NULL,
     gender       VARCHAR(8)                         NOT NULL,
     account      VARCHAR(16)                        NOT NULL,
@@ -18,6 +18,8 @@ CREATE TABLE IF NOT EXISTS sys_user
     updated_at   DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL ON UPDATE CURRENT_TIMESTAMP
 );
 
+CREATE UNIQUE INDEX uk_sys_user_account ON sys_user (account);
+
 INSERT INTO sys_user (id, name, gender, account, password, mobile_phone, birthday, enabled, created_at, updated_at)
 VALUES (1, '李四', 'female', 'lisi', '$2b$12$PsumwxjxX/o1RNOKpkc.Kuxea0izqSuhaod4PCudXoRh3zet1TASK',
         '17361631996', '2025-05-13', TRUE, '2025-05-18 12:39:53', '2025-05-18 12:39:53');
@@ -1,6 +1,11 @@
 use daoyi_module_demo::demo;
 use salvo::prelude::*;
 
+/// 初始化各模块，需在创建路由前调用
+pub fn init() {
+    demo::init();
+}
+
 pub fn create_router() -> Router {
     let demo_router = demo::create_router();
     Router::new().push(demo_router)
@@ -4,6 +4,7 @@ pub mod api;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    api::init();
     let router = api::create_router();
     app::run("server", router).await
 }